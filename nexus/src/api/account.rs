@@ -0,0 +1,120 @@
+//! Account name of the local player.
+//!
+//! The account name is received from ArcDPS via [`ACCOUNT_NAME`] and cached as owned [`String`].
+//! With the `"rtapi"` feature the RealTime API account name is used as fallback.
+//! The Mumble identity only contains the character name and is not used.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::account::{account_name, on_account_name_change, subscribe_account_name};
+//!
+//! subscribe_account_name().revert_on_unload();
+//! on_account_name_change(|name| {
+//!     use nexus::log::{log, LogLevel};
+//!     log(LogLevel::Info, "My Addon", format!("account name is {name}"));
+//! })
+//! .revert_on_unload();
+//!
+//! let name: Option<String> = account_name();
+//! ```
+
+use crate::{
+    callbacks::Callbacks,
+    event::{
+        arc::{ACCOUNT_NAME, REQUEST_ACCOUNT_NAME},
+        event_consume, event_raise_notification,
+    },
+    revertible::Revertible,
+    util::str_from_c,
+};
+use std::{ffi::c_char, sync::Mutex};
+
+/// Cached account name.
+static ACCOUNT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Registered change callbacks.
+static CALLBACKS: Callbacks<str> = Callbacks::new();
+
+/// Subscribes to the ArcDPS account name.
+///
+/// Requests the account name if none has been received yet.
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+pub fn subscribe_account_name() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let revert = ACCOUNT_NAME.subscribe(event_consume!(<c_char> |name| {
+        if let Some(name) = name {
+            if let Some(name) = unsafe { str_from_c(name) } {
+                set_account_name(name);
+            }
+        }
+    }));
+    if cached_account_name().is_none() {
+        request_account_name();
+    }
+    revert
+}
+
+/// Requests the account name from ArcDPS.
+#[inline]
+pub fn request_account_name() {
+    event_raise_notification(REQUEST_ACCOUNT_NAME.identifier)
+}
+
+/// Returns the account name.
+///
+/// Falls back to the RealTime API if no account name has been received.
+#[inline]
+pub fn account_name() -> Option<String> {
+    cached_account_name().or_else(fallback_account_name)
+}
+
+/// Returns the account name received from ArcDPS.
+#[inline]
+pub fn cached_account_name() -> Option<String> {
+    ACCOUNT.lock().unwrap().clone()
+}
+
+/// Registers a callback invoked when the account name changes.
+///
+/// Returns a [`Revertible`] to remove the callback.
+pub fn on_account_name_change(
+    callback: impl Fn(&str) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register((), callback)
+}
+
+/// Updates the cached account name and notifies callbacks on change.
+fn set_account_name(name: &str) {
+    let name = normalize(name);
+    if name.is_empty() {
+        return;
+    }
+
+    let mut account = ACCOUNT.lock().unwrap();
+    if account.as_deref() != Some(name) {
+        *account = Some(name.to_owned());
+        drop(account);
+
+        CALLBACKS.invoke(name);
+    }
+}
+
+/// Strips the leading colon ArcDPS prefixes account names with.
+fn normalize(name: &str) -> &str {
+    name.strip_prefix(':').unwrap_or(name)
+}
+
+#[cfg(feature = "rtapi")]
+fn fallback_account_name() -> Option<String> {
+    use crate::rtapi::RealTimeApi;
+
+    RealTimeApi::get()?
+        .read_player()
+        .map(|player| normalize(&player.account_name).to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(not(feature = "rtapi"))]
+fn fallback_account_name() -> Option<String> {
+    None
+}
@@ -0,0 +1,607 @@
+//! Chat message log & command router on top of Unofficial Extras.
+//!
+//! Messages received via [`CHAT_MESSAGE`] are kept as owned [`ChatMessage`] in a bounded [`ChatHistory`].
+//! Messages starting with the command prefix are routed to registered command handlers.
+//! Optionally the history is persisted to one file per day under the addon directory.
+//!
+//! Command handlers & log writes of the shared [`Chat`] run without holding its lock,
+//! so handlers may access the shared chat.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::chat::{register_command, set_persistence, subscribe_chat, with_chat_history};
+//!
+//! subscribe_chat().revert_on_unload();
+//! set_persistence("My Addon");
+//!
+//! register_command("roll", |message, args| {
+//!     // handle "!roll <args>"
+//! })
+//! .revert_on_unload();
+//!
+//! let last = with_chat_history(|history| history.iter().last().cloned());
+//! ```
+
+use crate::{
+    event::{event_consume, extras::CHAT_MESSAGE},
+    paths::get_addon_dir,
+    revertible::Revertible,
+};
+use arcdps::extras::message::{ChannelType, SquadMessage};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Default number of messages kept in the history.
+pub const DEFAULT_CAPACITY: usize = 500;
+
+/// Default command prefix.
+pub const DEFAULT_PREFIX: &str = "!";
+
+/// Chat command handler.
+///
+/// Receives the message and the arguments following the command name.
+pub type CommandHandler = Arc<dyn Fn(&ChatMessage, &str) + Send + Sync>;
+
+/// Shared chat state.
+static CHAT: Mutex<Chat> = Mutex::new(Chat::new());
+
+/// Last failed write of the shared chat log.
+static LOG_ERROR: Mutex<Option<io::Error>> = Mutex::new(None);
+
+/// Chat message as owned version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChatMessage {
+    /// Id of the channel.
+    pub channel_id: u32,
+
+    /// Whether the message was sent in party or squad chat.
+    pub channel_type: ChannelType,
+
+    /// Subgroup the message was sent to, 0 if sent to the whole squad.
+    pub subgroup: u8,
+
+    /// Whether the message is a squad broadcast.
+    pub is_broadcast: bool,
+
+    /// Timestamp of the message in ISO 8601 format.
+    pub timestamp: String,
+
+    /// Account name of the sender.
+    pub account_name: String,
+
+    /// Character name of the sender.
+    pub character_name: String,
+
+    /// Message text.
+    pub text: String,
+}
+
+impl ChatMessage {
+    /// Returns the date part of the timestamp as `YYYY-MM-DD`.
+    #[inline]
+    pub fn date(&self) -> Option<&str> {
+        self.timestamp.get(..10).filter(|date| {
+            date.bytes()
+                .all(|byte| byte.is_ascii_digit() || byte == b'-')
+        })
+    }
+
+    /// Returns the command name & arguments if the message starts with the prefix.
+    pub fn command(&self, prefix: &str) -> Option<(&str, &str)> {
+        let rest = self.text.trim_start().strip_prefix(prefix)?;
+        let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        (!name.is_empty()).then_some((name, args.trim()))
+    }
+}
+
+impl From<&SquadMessage> for ChatMessage {
+    fn from(message: &SquadMessage) -> Self {
+        Self {
+            channel_id: message.channel_id,
+            channel_type: message.channel_type,
+            subgroup: message.subgroup,
+            is_broadcast: message.is_broadcast(),
+            timestamp: message.timestamp_raw().to_owned(),
+            account_name: message.account_name().to_owned(),
+            character_name: message.character_name().to_owned(),
+            text: message.text().to_owned(),
+        }
+    }
+}
+
+/// Bounded history of chat messages.
+#[derive(Debug, Clone)]
+pub struct ChatHistory {
+    messages: VecDeque<ChatMessage>,
+    capacity: usize,
+}
+
+impl ChatHistory {
+    /// Creates a new empty history keeping at most `capacity` messages.
+    #[inline]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of messages kept.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of messages kept, dropping the oldest messages if necessary.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    /// Adds a message, dropping the oldest message if the history is full.
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push_back(message);
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        while self.messages.len() > self.capacity {
+            self.messages.pop_front();
+        }
+    }
+
+    /// Returns the number of messages.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Checks whether the history is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Returns an iterator over the messages from oldest to newest.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &ChatMessage> {
+        self.messages.iter()
+    }
+
+    /// Returns an iterator over the messages of the given channel type.
+    #[inline]
+    pub fn iter_channel(
+        &self,
+        channel_type: ChannelType,
+    ) -> impl DoubleEndedIterator<Item = &ChatMessage> {
+        self.iter()
+            .filter(move |message| message.channel_type == channel_type)
+    }
+
+    /// Removes all messages.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.messages.clear()
+    }
+}
+
+impl Default for ChatHistory {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// Routes prefixed chat messages to command handlers.
+pub struct CommandRouter {
+    prefix: Cow<'static, str>,
+    commands: BTreeMap<String, CommandHandler>,
+}
+
+impl CommandRouter {
+    /// Creates a new router without commands using the [default prefix](DEFAULT_PREFIX).
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            prefix: Cow::Borrowed(DEFAULT_PREFIX),
+            commands: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the command prefix.
+    #[inline]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Sets the command prefix.
+    ///
+    /// An empty prefix would treat every message as command, it is rejected & the previous prefix is kept.
+    /// Returns `false` if the prefix was rejected.
+    #[inline]
+    pub fn set_prefix(&mut self, prefix: impl Into<String>) -> bool {
+        let prefix = prefix.into();
+        let valid = !prefix.is_empty();
+        if valid {
+            self.prefix = prefix.into();
+        }
+        valid
+    }
+
+    /// Registers a handler for the command, replacing any previous handler.
+    ///
+    /// Command names are matched case-insensitive.
+    #[inline]
+    pub fn register(
+        &mut self,
+        name: impl AsRef<str>,
+        handler: impl Fn(&ChatMessage, &str) + Send + Sync + 'static,
+    ) {
+        self.commands
+            .insert(name.as_ref().to_lowercase(), Arc::new(handler));
+    }
+
+    /// Removes the handler for the command.
+    #[inline]
+    pub fn unregister(&mut self, name: impl AsRef<str>) {
+        self.commands.remove(&name.as_ref().to_lowercase());
+    }
+
+    /// Returns an iterator over the registered command names.
+    #[inline]
+    pub fn commands(&self) -> impl Iterator<Item = &str> {
+        self.commands.keys().map(String::as_str)
+    }
+
+    /// Returns the command handler for the message & the arguments following the command name.
+    pub fn resolve<'a>(&self, message: &'a ChatMessage) -> Option<(CommandHandler, &'a str)> {
+        let (name, args) = message.command(self.prefix())?;
+        let handler = self.commands.get(&name.to_lowercase())?;
+        Some((handler.clone(), args))
+    }
+
+    /// Routes the message to its command handler.
+    ///
+    /// Returns `true` if a handler was invoked.
+    pub fn route(&self, message: &ChatMessage) -> bool {
+        match self.resolve(message) {
+            Some((handler, args)) => {
+                handler(message, args);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for CommandRouter {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for CommandRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandRouter")
+            .field("prefix", &self.prefix())
+            .field("commands", &self.commands.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Writes chat messages to one file per day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLog {
+    dir: PathBuf,
+}
+
+impl ChatLog {
+    /// Creates a new chat log writing to the given directory.
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates a new chat log writing to the `chat` directory of the addon.
+    #[inline]
+    pub fn for_addon(name: impl AsRef<str>) -> Option<Self> {
+        get_addon_dir(name).map(|dir| Self::new(dir.join("chat")))
+    }
+
+    /// Returns the directory of the chat log.
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the file for the given date.
+    #[inline]
+    pub fn path(&self, date: &str) -> PathBuf {
+        self.dir.join(format!("{date}.log"))
+    }
+
+    /// Appends the message to the file of its day.
+    ///
+    /// Each message is written as a tab separated line of timestamp, channel, subgroup, account name, character name & text.
+    pub fn write(&self, message: &ChatMessage) -> io::Result<()> {
+        let date = message.date().unwrap_or("unknown");
+        fs::create_dir_all(&self.dir)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(date))?;
+        writeln!(
+            file,
+            "{}\t{:?}\t{}\t{}\t{}\t{}",
+            message.timestamp,
+            message.channel_type,
+            message.subgroup,
+            message.account_name,
+            message.character_name,
+            message.text.replace(['\r', '\n'], " ")
+        )
+    }
+}
+
+/// Chat history, command router & optional log.
+#[derive(Debug)]
+pub struct Chat {
+    /// Message history.
+    pub history: ChatHistory,
+
+    /// Command router.
+    pub router: CommandRouter,
+
+    /// Log persisting the messages.
+    pub log: Option<ChatLog>,
+}
+
+impl Chat {
+    /// Creates a new chat without commands or log.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            history: ChatHistory::new(DEFAULT_CAPACITY),
+            router: CommandRouter::new(),
+            log: None,
+        }
+    }
+
+    /// Processes a received message.
+    ///
+    /// The message is added to the history.
+    /// Writing it to the log & invoking its command handler is deferred to [`ProcessedMessage::dispatch`],
+    /// so the shared chat can be unlocked before.
+    pub fn process(&mut self, message: ChatMessage) -> ProcessedMessage {
+        self.history.push(message.clone());
+        let command = self
+            .router
+            .resolve(&message)
+            .map(|(handler, args)| (handler, args.to_owned()));
+        ProcessedMessage {
+            message,
+            log: self.log.clone(),
+            command,
+        }
+    }
+}
+
+/// Message added to a [`Chat`] with pending log write & command handler.
+#[must_use]
+pub struct ProcessedMessage {
+    message: ChatMessage,
+    log: Option<ChatLog>,
+    command: Option<(CommandHandler, String)>,
+}
+
+impl ProcessedMessage {
+    /// Returns the message.
+    #[inline]
+    pub fn message(&self) -> &ChatMessage {
+        &self.message
+    }
+
+    /// Writes the message to the log & invokes its command handler.
+    ///
+    /// A failed log write is returned after the handler was invoked.
+    ///
+    /// Returns `true` if the message was handled as command.
+    pub fn dispatch(self) -> io::Result<bool> {
+        let written = self
+            .log
+            .as_ref()
+            .map_or(Ok(()), |log| log.write(&self.message));
+        let handled = match &self.command {
+            Some((handler, args)) => {
+                handler(&self.message, args);
+                true
+            }
+            None => false,
+        };
+        written.map(|_| handled)
+    }
+}
+
+impl std::fmt::Debug for ProcessedMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessedMessage")
+            .field("message", &self.message)
+            .field("log", &self.log)
+            .field("command", &self.command.as_ref().map(|(_, args)| args))
+            .finish()
+    }
+}
+
+impl Default for Chat {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Subscribes the shared [`Chat`] to [`CHAT_MESSAGE`].
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+pub fn subscribe_chat() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CHAT_MESSAGE.subscribe(event_consume!(<SquadMessage> |message| {
+        if let Some(message) = message {
+            process_message(message.into());
+        }
+    }))
+}
+
+/// Processes a received message with the shared [`Chat`].
+///
+/// The log write & command handler run after the shared chat was unlocked.
+/// Failed log writes are stored, see [`take_chat_log_error`].
+fn process_message(message: ChatMessage) {
+    let processed = with_chat(|chat| chat.process(message));
+    if let Err(err) = processed.dispatch() {
+        #[cfg(feature = "log")]
+        ::log::warn!("failed to write chat log: {err}");
+        *LOG_ERROR.lock().unwrap() = Some(err);
+    }
+}
+
+/// Returns the last failed write of the shared chat log, if any.
+///
+/// The error is cleared afterwards.
+#[inline]
+pub fn take_chat_log_error() -> Option<io::Error> {
+    LOG_ERROR.lock().unwrap().take()
+}
+
+/// Accesses the shared [`Chat`].
+#[inline]
+pub fn with_chat<R>(body: impl FnOnce(&mut Chat) -> R) -> R {
+    body(&mut CHAT.lock().unwrap())
+}
+
+/// Accesses the shared [`ChatHistory`].
+#[inline]
+pub fn with_chat_history<R>(body: impl FnOnce(&ChatHistory) -> R) -> R {
+    with_chat(|chat| body(&chat.history))
+}
+
+/// Sets the number of messages kept in the shared [`ChatHistory`].
+#[inline]
+pub fn set_history_capacity(capacity: usize) {
+    with_chat(|chat| chat.history.set_capacity(capacity))
+}
+
+/// Sets the command prefix of the shared [`CommandRouter`].
+///
+/// Returns `false` if the prefix was empty & rejected.
+#[inline]
+pub fn set_command_prefix(prefix: impl Into<String>) -> bool {
+    with_chat(|chat| chat.router.set_prefix(prefix))
+}
+
+/// Registers a command handler with the shared [`CommandRouter`].
+///
+/// Handlers are invoked without holding the lock of the shared chat and may access it.
+///
+/// Returns a [`Revertible`] to remove the command.
+pub fn register_command(
+    name: impl AsRef<str>,
+    handler: impl Fn(&ChatMessage, &str) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let name = name.as_ref().to_owned();
+    with_chat(|chat| chat.router.register(&name, handler));
+    let revert = move || with_chat(|chat| chat.router.unregister(&name));
+    revert.into()
+}
+
+/// Enables persisting messages under the directory of the given addon.
+///
+/// Returns `false` if the addon directory is unavailable.
+#[inline]
+pub fn set_persistence(addon: impl AsRef<str>) -> bool {
+    let log = ChatLog::for_addon(addon);
+    let enabled = log.is_some();
+    with_chat(|chat| chat.log = log);
+    enabled
+}
+
+/// Disables persisting messages.
+#[inline]
+pub fn disable_persistence() {
+    with_chat(|chat| chat.log = None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(text: &str) -> ChatMessage {
+        ChatMessage {
+            channel_id: 0,
+            channel_type: ChannelType::Squad,
+            subgroup: 0,
+            is_broadcast: false,
+            timestamp: "2024-05-01T12:00:00+02:00".into(),
+            account_name: "Name.1234".into(),
+            character_name: "Character".into(),
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn history_and_commands() {
+        let mut history = ChatHistory::new(2);
+        for text in ["a", "b", "c"] {
+            history.push(message(text));
+        }
+        let texts: Vec<_> = history
+            .iter()
+            .map(|message| message.text.as_str())
+            .collect();
+        assert_eq!(texts, ["b", "c"]);
+
+        let msg = message("  !Roll  1 100 ");
+        assert_eq!(msg.command("!"), Some(("Roll", "1 100")));
+        assert_eq!(msg.date(), Some("2024-05-01"));
+        assert_eq!(message("!").command("!"), None);
+        assert_eq!(message("hello").command("!"), None);
+
+        let mut router = CommandRouter::new();
+        router.register("roll", |_, args| assert_eq!(args, "1 100"));
+        assert!(router.route(&msg));
+        assert!(!router.route(&message("!other")));
+
+        assert!(!router.set_prefix(""));
+        assert_eq!(router.prefix(), DEFAULT_PREFIX);
+        assert!(router.set_prefix("?"));
+        assert!(!router.route(&msg));
+
+        let mut chat = Chat::new();
+        chat.router
+            .register("roll", |_, args| assert_eq!(args, "1 100"));
+        let processed = chat.process(msg.clone());
+        assert_eq!(chat.history.len(), 1);
+        assert_eq!(processed.message(), &msg);
+        assert!(processed.dispatch().unwrap());
+        assert!(!chat.process(message("text")).dispatch().unwrap());
+    }
+
+    #[test]
+    fn shared_handler_accesses_chat() {
+        let revert = register_command("count", |message, _| {
+            // handler runs unlocked & sees the message in the history
+            let last = with_chat_history(|history| history.iter().last().cloned());
+            assert_eq!(last.as_ref(), Some(message));
+        })
+        .into_inner();
+        process_message(message("!count"));
+        revert();
+        assert_eq!(with_chat(|chat| chat.router.commands().count()), 0);
+    }
+}
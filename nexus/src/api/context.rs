@@ -0,0 +1,516 @@
+//! Game context combined from all available data sources.
+//!
+//! Each field is taken from the richest source providing it, in order:
+//! 1. RealTime API
+//! 2. Mumble link, with the `"mumble"` feature
+//! 3. Mumble identity from [`MUMBLE_IDENTITY_UPDATED`](crate::event::MUMBLE_IDENTITY_UPDATED)
+//! 4. [`NexusLink`]
+//! 5. System time
+//!
+//! Data from other sources is converted to the RealTime API types.
+//! The source of each field is available via [`GameContext::source`].
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::context::{current_game_context, subscribe_game_context, ContextField};
+//!
+//! subscribe_game_context().revert_on_unload();
+//!
+//! // later
+//! if let Some(context) = current_game_context() {
+//!     let map_id = context.map_id();
+//!     let source = context.source(ContextField::MapId);
+//! }
+//! ```
+
+use crate::{
+    data_link::{read_nexus_link, NexusLink},
+    event::{current_mumble_identity, subscribe_mumble_identity, MumbleIdentity},
+    game::Profession,
+    gui::{register_render, RenderType},
+    revertible::Revertible,
+    rtapi::{
+        CameraData, CharacterState, GameData, GameState, GroupData, PlayerData, RealTimeApi,
+        RealTimeSnapshot, WorldData,
+    },
+    tyria_time::TimeOfDay,
+    vec3::Vec3,
+};
+use std::{collections::BTreeMap, net::Ipv4Addr, sync::Mutex};
+
+/// Placeholder for unknown raw values.
+const UNKNOWN: u32 = u32::MAX;
+
+/// Default vertical field of view in radians.
+pub const DEFAULT_FOV: f32 = 1.222;
+
+/// Shared context, refreshed every frame.
+static CONTEXT: Mutex<Option<GameContext>> = Mutex::new(None);
+
+/// Source of a [`GameContext`] field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum ContextSource {
+    RealTime,
+    Mumble,
+    MumbleIdentity,
+    NexusLink,
+    SystemTime,
+}
+
+/// Field of a [`GameContext`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum ContextField {
+    IsGameplay,
+    GameState,
+    Language,
+    MapId,
+    MapType,
+    TimeOfDay,
+    AccountName,
+    CharacterName,
+    Position,
+    Facing,
+    Profession,
+    Specialization,
+    Mount,
+    CharacterState,
+    Group,
+    CameraPosition,
+    CameraFacing,
+    Fov,
+    ActionCamera,
+}
+
+/// Game context combined from all available data sources.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GameContext {
+    /// Whether the game is in gameplay.
+    pub is_gameplay: bool,
+
+    /// Game data, only available from the RealTime API.
+    pub game: Option<GameData>,
+
+    /// World data.
+    pub world: Option<WorldData>,
+
+    /// Player data.
+    ///
+    /// Positions are in inches, as provided by the RealTime API.
+    pub player: Option<PlayerData>,
+
+    /// Group data, only available from the RealTime API.
+    pub group: Option<GroupData>,
+
+    /// Camera data.
+    ///
+    /// Positions are in meters, as provided by the RealTime API.
+    pub camera: Option<CameraData>,
+
+    /// Sources of the provided fields.
+    pub sources: BTreeMap<ContextField, ContextSource>,
+}
+
+impl GameContext {
+    /// Creates a new empty context.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            is_gameplay: false,
+            game: None,
+            world: None,
+            player: None,
+            group: None,
+            camera: None,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Reads the context from all available sources.
+    pub fn read() -> Self {
+        let mut context = Self::new();
+        if let Some(snapshot) = RealTimeApi::get().and_then(|rtapi| rtapi.snapshot()) {
+            context.apply_realtime(snapshot);
+        }
+
+        #[cfg(feature = "mumble")]
+        if let Some(link) = crate::data_link::read_mumble_link() {
+            context.apply_mumble(&link);
+        }
+
+        if let Some(identity) = current_mumble_identity() {
+            context.apply_identity(&identity);
+        }
+        if let Some(link) = read_nexus_link() {
+            context.apply_nexus_link(&link);
+        }
+        context.apply_system_time();
+        context
+    }
+
+    /// Returns the source of a field.
+    ///
+    /// Returns [`None`] if no source provided the field.
+    #[inline]
+    pub fn source(&self, field: ContextField) -> Option<ContextSource> {
+        self.sources.get(&field).copied()
+    }
+
+    /// Checks whether a field was provided.
+    #[inline]
+    pub fn has(&self, field: ContextField) -> bool {
+        self.sources.contains_key(&field)
+    }
+
+    /// Returns the current map id.
+    #[inline]
+    pub fn map_id(&self) -> Option<u32> {
+        self.world
+            .as_ref()
+            .filter(|_| self.has(ContextField::MapId))
+            .map(|world| world.map_id)
+    }
+
+    /// Returns the character position in inches.
+    #[inline]
+    pub fn position(&self) -> Option<Vec3> {
+        self.player
+            .as_ref()
+            .filter(|_| self.has(ContextField::Position))
+            .map(|player| player.position())
+    }
+
+    /// Returns the character name.
+    #[inline]
+    pub fn character_name(&self) -> Option<&str> {
+        self.player
+            .as_ref()
+            .filter(|_| self.has(ContextField::CharacterName))
+            .map(|player| player.character_name.as_str())
+    }
+
+    /// Returns the character [`Profession`].
+    #[inline]
+    pub fn profession(&self) -> Option<Result<Profession, u32>> {
+        self.player
+            .as_ref()
+            .filter(|_| self.has(ContextField::Profession))
+            .map(Profession::try_from)
+    }
+
+    /// Marks a field as provided by the source.
+    ///
+    /// Returns `false` if the field was already provided by another source.
+    fn provide(&mut self, field: ContextField, source: ContextSource) -> bool {
+        if self.has(field) {
+            false
+        } else {
+            self.sources.insert(field, source);
+            true
+        }
+    }
+
+    fn world_mut(&mut self) -> &mut WorldData {
+        self.world.get_or_insert_with(|| WorldData {
+            time_of_day: Err(UNKNOWN),
+            map_id: 0,
+            map_type: Err(UNKNOWN),
+            ip_address: Ipv4Addr::UNSPECIFIED,
+            cursor: [0.0; 3],
+        })
+    }
+
+    fn player_mut(&mut self) -> &mut PlayerData {
+        self.player.get_or_insert_with(|| PlayerData {
+            account_name: String::new(),
+            character_name: String::new(),
+            character_position: [0.0; 3],
+            character_facing: [0.0; 3],
+            profession: 0,
+            elite_specialization: 0,
+            mount_index: 0,
+            character_state: CharacterState::empty(),
+        })
+    }
+
+    fn camera_mut(&mut self) -> &mut CameraData {
+        self.camera.get_or_insert_with(|| CameraData {
+            camera_position: [0.0; 3],
+            camera_facing: [0.0; 3],
+            camera_fov: DEFAULT_FOV,
+            is_action_camera: false,
+        })
+    }
+
+    /// Applies a RealTime API snapshot to fields not provided yet.
+    pub fn apply_realtime(&mut self, snapshot: RealTimeSnapshot) {
+        let RealTimeSnapshot {
+            game,
+            world,
+            player,
+            group,
+            camera,
+        } = snapshot;
+        let source = ContextSource::RealTime;
+
+        if self.provide(ContextField::IsGameplay, source) {
+            self.is_gameplay = game.game_state == Ok(GameState::Gameplay);
+        }
+        if self.game.is_none() {
+            self.provide(ContextField::GameState, source);
+            self.provide(ContextField::Language, source);
+            self.game = Some(game);
+        }
+        if self.group.is_none() {
+            self.provide(ContextField::Group, source);
+            self.group = Some(group);
+        }
+
+        if self.provide(ContextField::MapId, source) {
+            self.world_mut().map_id = world.map_id;
+        }
+        if self.provide(ContextField::MapType, source) {
+            self.world_mut().map_type = world.map_type;
+        }
+        if self.provide(ContextField::TimeOfDay, source) {
+            self.world_mut().time_of_day = world.time_of_day;
+        }
+        let target = self.world_mut();
+        target.ip_address = world.ip_address;
+        target.cursor = world.cursor;
+
+        if self.provide(ContextField::AccountName, source) {
+            self.player_mut().account_name = player.account_name;
+        }
+        if self.provide(ContextField::CharacterName, source) {
+            self.player_mut().character_name = player.character_name;
+        }
+        if self.provide(ContextField::Position, source) {
+            self.player_mut().character_position = player.character_position;
+        }
+        if self.provide(ContextField::Facing, source) {
+            self.player_mut().character_facing = player.character_facing;
+        }
+        if self.provide(ContextField::Profession, source) {
+            self.player_mut().profession = player.profession;
+        }
+        if self.provide(ContextField::Specialization, source) {
+            self.player_mut().elite_specialization = player.elite_specialization;
+        }
+        if self.provide(ContextField::Mount, source) {
+            self.player_mut().mount_index = player.mount_index;
+        }
+        if self.provide(ContextField::CharacterState, source) {
+            self.player_mut().character_state = player.character_state;
+        }
+
+        if self.provide(ContextField::CameraPosition, source) {
+            self.camera_mut().camera_position = camera.camera_position;
+        }
+        if self.provide(ContextField::CameraFacing, source) {
+            self.camera_mut().camera_facing = camera.camera_facing;
+        }
+        if self.provide(ContextField::Fov, source) {
+            self.camera_mut().camera_fov = camera.camera_fov;
+        }
+        if self.provide(ContextField::ActionCamera, source) {
+            self.camera_mut().is_action_camera = camera.is_action_camera;
+        }
+    }
+
+    /// Applies Mumble link data to fields not provided yet.
+    ///
+    /// Inactive links are ignored.
+    /// Of the character state only [`CharacterState::IsInCombat`] is provided.
+    #[cfg(feature = "mumble")]
+    pub fn apply_mumble(&mut self, link: &crate::data_link::MumbleLink) {
+        use crate::data_link::mumble::{MumbleLinkExt, UiState};
+        if link.ui_tick == 0 {
+            return;
+        }
+        let source = ContextSource::Mumble;
+
+        if self.provide(ContextField::MapId, source) {
+            self.world_mut().map_id = link.context.map_id;
+        }
+        if self.provide(ContextField::MapType, source) {
+            self.world_mut().map_type = link.context.map_type.try_into();
+        }
+
+        if self.provide(ContextField::Position, source) {
+            self.player_mut().character_position = link.avatar_position().to_inches().into();
+        }
+        if self.provide(ContextField::Facing, source) {
+            self.player_mut().character_facing = link.avatar_front().into();
+        }
+        if self.provide(ContextField::Mount, source) {
+            self.player_mut().mount_index = link.context.mount_index.into();
+        }
+        if self.provide(ContextField::CharacterState, source) {
+            self.player_mut().character_state.set(
+                CharacterState::IsInCombat,
+                link.context.ui_state.contains(UiState::IS_IN_COMBAT),
+            );
+        }
+
+        if self.provide(ContextField::CameraPosition, source) {
+            self.camera_mut().camera_position = link.camera_position().into();
+        }
+        if self.provide(ContextField::CameraFacing, source) {
+            self.camera_mut().camera_facing = link.camera_front().into();
+        }
+    }
+
+    /// Applies the Mumble identity to fields not provided yet.
+    pub fn apply_identity(&mut self, identity: &MumbleIdentity) {
+        let source = ContextSource::MumbleIdentity;
+        if self.provide(ContextField::MapId, source) {
+            self.world_mut().map_id = identity.map_id;
+        }
+        if self.provide(ContextField::CharacterName, source) {
+            self.player_mut().character_name = identity.name.clone();
+        }
+        if self.provide(ContextField::Profession, source) {
+            self.player_mut().profession = identity
+                .profession
+                .map_or_else(|raw| raw, |profession| profession.into());
+        }
+        if self.provide(ContextField::Specialization, source) {
+            self.player_mut().elite_specialization = identity
+                .specialization
+                .map_or_else(|raw| raw, |specialization| specialization.into());
+        }
+        if self.provide(ContextField::Fov, source) {
+            self.camera_mut().camera_fov = identity.fov;
+        }
+    }
+
+    /// Applies the [`NexusLink`] to fields not provided yet.
+    pub fn apply_nexus_link(&mut self, link: &NexusLink) {
+        if self.provide(ContextField::IsGameplay, ContextSource::NexusLink) {
+            self.is_gameplay = link.is_gameplay;
+        }
+    }
+
+    /// Applies the time of day computed from the system time if not provided yet.
+    pub fn apply_system_time(&mut self) {
+        if self.provide(ContextField::TimeOfDay, ContextSource::SystemTime) {
+            self.world_mut().time_of_day = Ok(TimeOfDay::now());
+        }
+    }
+}
+
+impl Default for GameContext {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Refreshes the shared [`GameContext`] every frame.
+///
+/// Also subscribes to Mumble identity updates.
+///
+/// Returns a [`Revertible`] to stop refreshing.
+pub fn subscribe_game_context() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    extern "C-unwind" fn refresh_game_context() {
+        let context = GameContext::read();
+        *CONTEXT.lock().unwrap() = Some(context);
+    }
+
+    let identity = subscribe_mumble_identity().into_inner();
+    let render = register_render(RenderType::PreRender, refresh_game_context).into_inner();
+    let revert = move || {
+        render();
+        identity();
+        *CONTEXT.lock().unwrap() = None;
+    };
+    revert.into()
+}
+
+/// Returns the current shared [`GameContext`].
+///
+/// Requires [`subscribe_game_context`] to be called first.
+#[inline]
+pub fn current_game_context() -> Option<GameContext> {
+    CONTEXT.lock().unwrap().clone()
+}
+
+/// Accesses the current shared [`GameContext`].
+///
+/// Requires [`subscribe_game_context`] to be called first.
+#[inline]
+pub fn with_game_context<R>(body: impl FnOnce(Option<&GameContext>) -> R) -> R {
+    body(CONTEXT.lock().unwrap().as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Specialization;
+
+    #[test]
+    fn priority() {
+        let identity = MumbleIdentity {
+            name: "Character".into(),
+            profession: Ok(Profession::Guardian),
+            specialization: Ok(Specialization::Firebrand),
+            race: Err(0),
+            map_id: 15,
+            world_id: 0,
+            team_color_id: 0,
+            is_commander: false,
+            fov: 1.0,
+            ui_size: 1,
+        };
+        let mut context = GameContext::new();
+        context.apply_identity(&identity);
+        context.apply_system_time();
+
+        assert_eq!(context.map_id(), Some(15));
+        assert_eq!(context.character_name(), Some("Character"));
+        assert_eq!(context.profession(), Some(Ok(Profession::Guardian)));
+        assert_eq!(context.position(), None);
+        assert_eq!(
+            context.source(ContextField::MapId),
+            Some(ContextSource::MumbleIdentity)
+        );
+        assert_eq!(
+            context.source(ContextField::TimeOfDay),
+            Some(ContextSource::SystemTime)
+        );
+
+        // already provided fields are kept
+        context.apply_identity(&MumbleIdentity {
+            map_id: 50,
+            ..identity
+        });
+        assert_eq!(context.map_id(), Some(15));
+    }
+}
@@ -0,0 +1,300 @@
+//! Conversion between world, map & continent coordinates.
+//!
+//! Map coordinates are in inches with the Y axis pointing north.
+//! Continent coordinates are in continent units with the Y axis pointing south.
+//! Each map is placed on the continent by its map & continent rectangle, as provided by the GW2 API.
+//!
+//! World positions map their X & Z axes to the map X & Y axes.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{coords::MapRects, vec3::{Units, Vec3}};
+//!
+//! # #[cfg(feature = "json")]
+//! # fn main() -> std::io::Result<()> {
+//! let maps = MapRects::load("addons/MyAddon/maps.json")?;
+//! if let Some(map) = maps.get(15) {
+//!     let continent = map.world_to_continent(Vec3::new(0.0, 0.0, 0.0), Units::Meters);
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "json"))]
+//! # fn main() {}
+//! ```
+
+use crate::vec3::{Units, Vec3};
+use std::collections::BTreeMap;
+
+/// Rectangle as top left & bottom right corner.
+///
+/// For map rectangles the first corner is the bottom left, as the map Y axis points north.
+pub type Rect = [[f32; 2]; 2];
+
+/// Map placement on the continent.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapRect {
+    /// Map id.
+    pub id: u32,
+
+    /// Map name.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub name: String,
+
+    /// Map bounds in map coordinates.
+    pub map_rect: Rect,
+
+    /// Map bounds in continent coordinates.
+    pub continent_rect: Rect,
+}
+
+impl MapRect {
+    /// Creates a new map rectangle.
+    #[inline]
+    pub fn new(id: u32, map_rect: Rect, continent_rect: Rect) -> Self {
+        Self {
+            id,
+            name: String::new(),
+            map_rect,
+            continent_rect,
+        }
+    }
+
+    /// Converts map coordinates to continent coordinates.
+    pub fn map_to_continent(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        let [[mx0, my0], [mx1, my1]] = self.map_rect;
+        let [[cx0, cy0], [cx1, cy1]] = self.continent_rect;
+        [
+            cx0 + (x - mx0) / (mx1 - mx0) * (cx1 - cx0),
+            cy0 + (my1 - y) / (my1 - my0) * (cy1 - cy0),
+        ]
+    }
+
+    /// Converts continent coordinates to map coordinates.
+    pub fn continent_to_map(&self, [x, y]: [f32; 2]) -> [f32; 2] {
+        let [[mx0, my0], [mx1, my1]] = self.map_rect;
+        let [[cx0, cy0], [cx1, cy1]] = self.continent_rect;
+        [
+            mx0 + (x - cx0) / (cx1 - cx0) * (mx1 - mx0),
+            my1 - (y - cy0) / (cy1 - cy0) * (my1 - my0),
+        ]
+    }
+
+    /// Converts a world position to map coordinates.
+    #[inline]
+    pub fn world_to_map(&self, position: impl Into<Vec3>, units: Units) -> [f32; 2] {
+        let position = units.position_to_meters(position).to_inches();
+        [position.x, position.z]
+    }
+
+    /// Converts a world position to continent coordinates.
+    #[inline]
+    pub fn world_to_continent(&self, position: impl Into<Vec3>, units: Units) -> [f32; 2] {
+        self.map_to_continent(self.world_to_map(position, units))
+    }
+
+    /// Converts map coordinates to a world position at the given height, all in the given units.
+    #[inline]
+    pub fn map_to_world(&self, [x, y]: [f32; 2], height: f32, units: Units) -> Vec3 {
+        let inches = Vec3::new(x, Units::Inches.meters_to(units.to_meters(height)), y);
+        match units {
+            Units::Inches => inches,
+            Units::Meters => inches.to_meters(),
+        }
+    }
+
+    /// Converts continent coordinates to a world position at the given height, all in the given units.
+    #[inline]
+    pub fn continent_to_world(&self, continent: [f32; 2], height: f32, units: Units) -> Vec3 {
+        self.map_to_world(self.continent_to_map(continent), height, units)
+    }
+
+    /// Checks whether the map coordinates are inside the map bounds.
+    #[inline]
+    pub fn contains_map(&self, point: [f32; 2]) -> bool {
+        contains(self.map_rect, point)
+    }
+
+    /// Checks whether the continent coordinates are inside the map bounds.
+    #[inline]
+    pub fn contains_continent(&self, point: [f32; 2]) -> bool {
+        contains(self.continent_rect, point)
+    }
+}
+
+fn contains([[x0, y0], [x1, y1]]: Rect, [x, y]: [f32; 2]) -> bool {
+    (x0.min(x1)..=x0.max(x1)).contains(&x) && (y0.min(y1)..=y0.max(y1)).contains(&y)
+}
+
+/// Table of [`MapRect`]s by map id.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MapRects {
+    maps: BTreeMap<u32, MapRect>,
+}
+
+impl MapRects {
+    /// Creates a new empty table.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            maps: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a map, replacing a previous map with the same id.
+    #[inline]
+    pub fn insert(&mut self, map: MapRect) {
+        self.maps.insert(map.id, map);
+    }
+
+    /// Returns the map with the given id.
+    #[inline]
+    pub fn get(&self, id: u32) -> Option<&MapRect> {
+        self.maps.get(&id)
+    }
+
+    /// Returns an iterator over all maps.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &MapRect> {
+        self.maps.values()
+    }
+
+    /// Returns the number of maps.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    /// Checks whether the table is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Parses a table from JSON.
+    ///
+    /// Accepts a list of maps or an object with maps by id, as returned by the GW2 API.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Table {
+            List(Vec<MapRect>),
+            Object(BTreeMap<String, MapRect>),
+        }
+
+        let maps = match serde_json::from_str(json)? {
+            Table::List(maps) => maps,
+            Table::Object(maps) => maps.into_values().collect(),
+        };
+        Ok(maps.into_iter().collect())
+    }
+
+    /// Loads a table from a JSON file.
+    #[cfg(feature = "json")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl FromIterator<MapRect> for MapRects {
+    fn from_iter<T: IntoIterator<Item = MapRect>>(iter: T) -> Self {
+        Self {
+            maps: iter.into_iter().map(|map| (map.id, map)).collect(),
+        }
+    }
+}
+
+impl Extend<MapRect> for MapRects {
+    fn extend<T: IntoIterator<Item = MapRect>>(&mut self, iter: T) {
+        self.maps.extend(iter.into_iter().map(|map| (map.id, map)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::INCHES_PER_METER;
+
+    fn queensdale() -> MapRect {
+        MapRect::new(
+            15,
+            [[-43008.0, -27648.0], [43008.0, 30720.0]],
+            [[9856.0, 11648.0], [13440.0, 14080.0]],
+        )
+    }
+
+    fn assert_near([ax, ay]: [f32; 2], [bx, by]: [f32; 2]) {
+        assert!(
+            (ax - bx).abs() < 0.01 && (ay - by).abs() < 0.01,
+            "{:?} != {:?}",
+            [ax, ay],
+            [bx, by]
+        );
+    }
+
+    #[test]
+    fn map_continent() {
+        let map = queensdale();
+        assert_near(map.map_to_continent([-43008.0, 30720.0]), [9856.0, 11648.0]);
+        assert_near(
+            map.map_to_continent([43008.0, -27648.0]),
+            [13440.0, 14080.0],
+        );
+        assert_near(map.map_to_continent([0.0, 1536.0]), [11648.0, 12864.0]);
+        assert_near(map.continent_to_map([11648.0, 12864.0]), [0.0, 1536.0]);
+        assert!(map.contains_continent([11648.0, 12864.0]));
+        assert!(!map.contains_map([50000.0, 0.0]));
+    }
+
+    #[test]
+    fn world() {
+        let map = queensdale();
+        let meters = Vec3::new(
+            -43008.0 / INCHES_PER_METER,
+            10.0,
+            30720.0 / INCHES_PER_METER,
+        );
+        assert_near(
+            map.world_to_continent(meters, Units::Meters),
+            [9856.0, 11648.0],
+        );
+        assert_near(
+            map.world_to_continent([0.0, 0.0, 1536.0], Units::Inches),
+            [11648.0, 12864.0],
+        );
+
+        let world = map.continent_to_world([11648.0, 12864.0], 100.0, Units::Inches);
+        assert!(
+            (world.x).abs() < 0.01
+                && (world.y - 100.0).abs() < 0.01
+                && (world.z - 1536.0).abs() < 0.01
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn load_fixture() {
+        let maps = MapRects::from_json(include_str!("maps.json")).unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(
+            maps.get(15),
+            Some(&MapRect {
+                name: "Queensdale".into(),
+                ..queensdale()
+            })
+        );
+        assert_near(
+            maps.get(1).unwrap().map_to_continent([0.0, 0.0]),
+            [1536.0, 2560.0],
+        );
+
+        let object = r#"{ "15": { "id": 15, "map_rect": [[-43008, -27648], [43008, 30720]], "continent_rect": [[9856, 11648], [13440, 14080]] } }"#;
+        assert_eq!(
+            MapRects::from_json(object).unwrap().get(15),
+            Some(&queensdale())
+        );
+    }
+}
@@ -1,8 +1,11 @@
 //! Data link for sharing resources.
 //!
+//! Use [`SharedResource`] for typed & versioned resources validated on read.
+//!
 //! Enable the `"mumble"` or `"mumble_json"` feature for Mumble link bindings.
 
 mod nexus;
+mod shared;
 
 /// Mumble link bindings.
 #[cfg(feature = "mumble")]
@@ -12,7 +15,7 @@ pub mod mumble;
 #[cfg(feature = "rtapi")]
 pub mod rtapi;
 
-pub use self::nexus::*;
+pub use self::{nexus::*, shared::*};
 
 #[cfg(feature = "mumble")]
 pub use self::mumble::{get_mumble_link, get_mumble_link_ptr, read_mumble_link, MumbleLink};
@@ -40,6 +43,7 @@ pub fn get_resource<T>(identifier: impl AsRef<str>) -> *const T {
 ///
 /// # Safety
 /// The caller must ensure the data associated with the given identifier is of type `T`.
+/// See [`SharedResource`] for a validated alternative.
 pub unsafe fn read_resource<T>(identifier: impl AsRef<str>) -> Option<T> {
     let ptr = get_resource::<T>(identifier);
     let valid = !ptr.is_null();
@@ -47,6 +51,8 @@ pub unsafe fn read_resource<T>(identifier: impl AsRef<str>) -> Option<T> {
 }
 
 /// Creates a new shared resource.
+///
+/// See [`SharedResource`] for a typed & versioned alternative.
 pub fn share_resource<T>(identifier: impl AsRef<str>) -> *mut T {
     let identifier = str_to_c(identifier, "failed to convert data link identifier");
     let DataLinkApi { share, .. } = AddonApi::get().data_link;
@@ -0,0 +1,69 @@
+pub mod watcher;
+
+pub use gw2_mumble::{LinkedMem as MumbleLink, *};
+
+use crate::{
+    data_link::{get_resource, read_resource},
+    vec3::Vec3,
+};
+
+/// Mumble link identifier.
+pub const MUMBLE_LINK: &str = "DL_MUMBLE_LINK";
+
+/// Returns the shared [`MumbleLink`] pointer.
+#[inline]
+pub fn get_mumble_link_ptr() -> *const MumbleLink {
+    get_resource(MUMBLE_LINK)
+}
+
+/// Returns the shared [`MumbleLink`] pointer as [`MumblePtr`].
+#[inline]
+pub fn get_mumble_link() -> Option<MumblePtr> {
+    let ptr = get_mumble_link_ptr().cast_mut();
+    unsafe { MumblePtr::new(ptr) }
+}
+
+/// Reads the shared [`MumbleLink`] data.
+#[inline]
+pub fn read_mumble_link() -> Option<MumbleLink> {
+    unsafe { read_resource(MUMBLE_LINK) }
+}
+
+/// Typed position accessors for [`MumbleLink`].
+///
+/// MumbleLink positions are in meters.
+pub trait MumbleLinkExt {
+    /// Returns the avatar position as [`Vec3`].
+    fn avatar_position(&self) -> Vec3;
+
+    /// Returns the avatar facing direction as [`Vec3`].
+    fn avatar_front(&self) -> Vec3;
+
+    /// Returns the camera position as [`Vec3`].
+    fn camera_position(&self) -> Vec3;
+
+    /// Returns the camera facing direction as [`Vec3`].
+    fn camera_front(&self) -> Vec3;
+}
+
+impl MumbleLinkExt for MumbleLink {
+    #[inline]
+    fn avatar_position(&self) -> Vec3 {
+        self.avatar.position.into()
+    }
+
+    #[inline]
+    fn avatar_front(&self) -> Vec3 {
+        self.avatar.front.into()
+    }
+
+    #[inline]
+    fn camera_position(&self) -> Vec3 {
+        self.camera.position.into()
+    }
+
+    #[inline]
+    fn camera_front(&self) -> Vec3 {
+        self.camera.front.into()
+    }
+}
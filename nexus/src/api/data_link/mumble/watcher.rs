@@ -0,0 +1,438 @@
+//! Change notifications for Mumble link data.
+//!
+//! The watcher is polled once per frame and compares the current link with the previous frame.
+//! The link is considered stale if its `ui_tick` does not advance, for example while the game is paused or minimized.
+//!
+//! Character & identity changes require the `"mumble_json"` feature.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::data_link::mumble::{
+//!     watcher::{on_mumble_change, subscribe_mumble_watcher, MumbleChange, MumbleChangeKind},
+//!     UiState,
+//! };
+//!
+//! subscribe_mumble_watcher().revert_on_unload();
+//!
+//! on_mumble_change(MumbleChangeKind::UiState, |change| {
+//!     if change.gained(UiState::IS_MAP_OPEN) {
+//!         // map opened
+//!     }
+//! })
+//! .revert_on_unload();
+//! ```
+
+use super::{read_mumble_link, MumbleLink, UiState};
+use crate::{
+    callbacks::Callbacks,
+    game::MountIndex,
+    gui::{register_render, RenderType},
+    revertible::Revertible,
+};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Shared watcher.
+static WATCHER: Mutex<MumbleWatcher> = Mutex::new(MumbleWatcher::new());
+
+/// Registered change callbacks.
+static CALLBACKS: Callbacks<MumbleChange, Option<MumbleChangeKind>> = Callbacks::new();
+
+/// Kind of a [`MumbleChange`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum MumbleChangeKind {
+    Stale,
+    Map,
+    Mount,
+    UiState,
+    Character,
+    Identity,
+}
+
+/// Change of Mumble link data between frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MumbleChange {
+    /// Link stopped or resumed updating.
+    Stale { is_stale: bool },
+
+    /// Map changed.
+    Map { from: u32, to: u32 },
+
+    /// Mount changed.
+    Mount {
+        from: Result<MountIndex, u32>,
+        to: Result<MountIndex, u32>,
+    },
+
+    /// UI state flags changed.
+    UiState { from: UiState, to: UiState },
+
+    /// Character switched.
+    Character { from: String, to: String },
+
+    /// Identity JSON changed.
+    Identity { from: String, to: String },
+}
+
+impl MumbleChange {
+    /// Returns the [`MumbleChangeKind`] of the change.
+    #[inline]
+    pub fn kind(&self) -> MumbleChangeKind {
+        match self {
+            Self::Stale { .. } => MumbleChangeKind::Stale,
+            Self::Map { .. } => MumbleChangeKind::Map,
+            Self::Mount { .. } => MumbleChangeKind::Mount,
+            Self::UiState { .. } => MumbleChangeKind::UiState,
+            Self::Character { .. } => MumbleChangeKind::Character,
+            Self::Identity { .. } => MumbleChangeKind::Identity,
+        }
+    }
+
+    /// Checks whether the UI state flags were set with this change.
+    ///
+    /// For example [`UiState::IS_MAP_OPEN`] for opening the map,
+    /// [`UiState::TEXTBOX_HAS_FOCUS`] for focusing chat or [`UiState::IS_IN_COMBAT`] for entering combat.
+    #[inline]
+    pub fn gained(&self, flags: UiState) -> bool {
+        match self {
+            Self::UiState { from, to } => !from.contains(flags) && to.contains(flags),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the UI state flags were cleared with this change.
+    #[inline]
+    pub fn lost(&self, flags: UiState) -> bool {
+        match self {
+            Self::UiState { from, to } => from.contains(flags) && !to.contains(flags),
+            _ => false,
+        }
+    }
+
+    /// Parses the new identity of an identity change.
+    #[cfg(feature = "mumble_json")]
+    #[inline]
+    pub fn identity(&self) -> Option<super::Identity> {
+        match self {
+            Self::Identity { to, .. } => serde_json::from_str(to).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the identity JSON of the link.
+#[inline]
+pub fn identity_json(link: &MumbleLink) -> String {
+    let len = link
+        .identity
+        .iter()
+        .position(|unit| *unit == 0)
+        .unwrap_or(link.identity.len());
+    String::from_utf16_lossy(&link.identity[..len])
+}
+
+/// Watched state of a frame.
+#[derive(Debug, Clone, PartialEq)]
+struct WatchState {
+    map_id: u32,
+    mount: Result<MountIndex, u32>,
+    ui_state: UiState,
+
+    #[cfg(feature = "mumble_json")]
+    identity: String,
+
+    #[cfg(feature = "mumble_json")]
+    character_name: Option<String>,
+}
+
+impl WatchState {
+    fn read(link: &MumbleLink) -> Self {
+        #[cfg(feature = "mumble_json")]
+        let identity = identity_json(link);
+        Self {
+            map_id: link.context.map_id,
+            mount: u32::from(link.context.mount_index).try_into(),
+            ui_state: link.context.ui_state,
+            #[cfg(feature = "mumble_json")]
+            character_name: serde_json::from_str::<super::Identity>(&identity)
+                .ok()
+                .map(|identity| identity.name),
+            #[cfg(feature = "mumble_json")]
+            identity,
+        }
+    }
+
+    fn diff(&self, current: &Self) -> Vec<MumbleChange> {
+        let mut changes = Vec::new();
+        if self.map_id != current.map_id {
+            changes.push(MumbleChange::Map {
+                from: self.map_id,
+                to: current.map_id,
+            });
+        }
+        if self.mount != current.mount {
+            changes.push(MumbleChange::Mount {
+                from: self.mount,
+                to: current.mount,
+            });
+        }
+        if self.ui_state != current.ui_state {
+            changes.push(MumbleChange::UiState {
+                from: self.ui_state,
+                to: current.ui_state,
+            });
+        }
+        #[cfg(feature = "mumble_json")]
+        {
+            if let (Some(from), Some(to)) = (&self.character_name, &current.character_name) {
+                if from != to {
+                    changes.push(MumbleChange::Character {
+                        from: from.clone(),
+                        to: to.clone(),
+                    });
+                }
+            }
+            if self.identity != current.identity {
+                changes.push(MumbleChange::Identity {
+                    from: self.identity.clone(),
+                    to: current.identity.clone(),
+                });
+            }
+        }
+        changes
+    }
+}
+
+/// Detects changes of Mumble link data between polls.
+#[derive(Debug, Clone)]
+pub struct MumbleWatcher {
+    previous: Option<WatchState>,
+    ui_tick: u32,
+    last_tick: Option<Instant>,
+    is_stale: bool,
+    stale_after: Duration,
+}
+
+impl MumbleWatcher {
+    /// Default duration without `ui_tick` updates until the link is considered stale.
+    pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(2);
+
+    /// Creates a new watcher without previous state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            previous: None,
+            ui_tick: 0,
+            last_tick: None,
+            is_stale: true,
+            stale_after: Self::DEFAULT_STALE_AFTER,
+        }
+    }
+
+    /// Sets the duration without `ui_tick` updates until the link is considered stale.
+    #[inline]
+    pub fn set_stale_after(&mut self, duration: Duration) {
+        self.stale_after = duration;
+    }
+
+    /// Checks whether the link is currently stale.
+    #[inline]
+    pub fn is_stale(&self) -> bool {
+        self.is_stale
+    }
+
+    /// Polls the link & returns the changes since the last poll.
+    ///
+    /// The first poll returns no changes except for the link becoming active.
+    /// Data of stale links is not compared.
+    pub fn poll(&mut self, link: &MumbleLink, now: Instant) -> Vec<MumbleChange> {
+        let mut changes = Vec::new();
+
+        if link.ui_tick != self.ui_tick {
+            self.ui_tick = link.ui_tick;
+            self.last_tick = Some(now);
+        }
+        let is_stale = link.ui_tick == 0
+            || self
+                .last_tick
+                .map_or(true, |last| now.duration_since(last) >= self.stale_after);
+        if is_stale != self.is_stale {
+            self.is_stale = is_stale;
+            changes.push(MumbleChange::Stale { is_stale });
+        }
+        if is_stale {
+            return changes;
+        }
+
+        let current = WatchState::read(link);
+        if let Some(previous) = &self.previous {
+            changes.extend(previous.diff(&current));
+        }
+        self.previous = Some(current);
+        changes
+    }
+
+    /// Clears the previous state.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self {
+            stale_after: self.stale_after,
+            ..Self::new()
+        };
+    }
+}
+
+impl Default for MumbleWatcher {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Registers the shared [`MumbleWatcher`] to be polled every frame.
+///
+/// Returns a [`Revertible`] to revert the register.
+pub fn subscribe_mumble_watcher() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    extern "C-unwind" fn poll_mumble_watcher() {
+        let Some(link) = read_mumble_link() else {
+            return;
+        };
+        let changes = WATCHER.lock().unwrap().poll(&link, Instant::now());
+        for change in &changes {
+            CALLBACKS.invoke_for(change, change.kind());
+        }
+    }
+
+    let render = register_render(RenderType::PreRender, poll_mumble_watcher).into_inner();
+    let revert = move || {
+        render();
+        WATCHER.lock().unwrap().reset();
+    };
+    revert.into()
+}
+
+/// Accesses the shared [`MumbleWatcher`].
+#[inline]
+pub fn with_mumble_watcher<R>(body: impl FnOnce(&mut MumbleWatcher) -> R) -> R {
+    body(&mut WATCHER.lock().unwrap())
+}
+
+/// Registers a callback invoked for changes of the given [`MumbleChangeKind`].
+///
+/// Returns a [`Revertible`] to remove the callback.
+#[inline]
+pub fn on_mumble_change(
+    kind: MumbleChangeKind,
+    callback: impl Fn(&MumbleChange) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register(Some(kind), callback)
+}
+
+/// Registers a callback invoked for all changes.
+///
+/// Returns a [`Revertible`] to remove the callback.
+#[inline]
+pub fn on_any_mumble_change(
+    callback: impl Fn(&MumbleChange) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register(None, callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::mem;
+
+    #[test]
+    fn diff() {
+        let previous = WatchState {
+            map_id: 15,
+            mount: Ok(MountIndex::None),
+            ui_state: UiState::GAME_HAS_FOCUS,
+            #[cfg(feature = "mumble_json")]
+            identity: String::new(),
+            #[cfg(feature = "mumble_json")]
+            character_name: Some("Character".into()),
+        };
+        let current = WatchState {
+            map_id: 50,
+            ui_state: UiState::GAME_HAS_FOCUS | UiState::IS_MAP_OPEN,
+            #[cfg(feature = "mumble_json")]
+            character_name: Some("Other".into()),
+            ..previous.clone()
+        };
+
+        let changes = previous.diff(&current);
+        assert_eq!(changes[0], MumbleChange::Map { from: 15, to: 50 });
+        assert!(changes[1].gained(UiState::IS_MAP_OPEN));
+        assert!(!changes[1].lost(UiState::GAME_HAS_FOCUS));
+        #[cfg(feature = "mumble_json")]
+        assert_eq!(changes[2].kind(), MumbleChangeKind::Character);
+        assert_eq!(
+            changes.len(),
+            if cfg!(feature = "mumble_json") { 3 } else { 2 }
+        );
+        assert!(previous.diff(&previous).is_empty());
+    }
+
+    #[test]
+    fn poll_staleness() {
+        // link data is plain integers & floats, all zeros is a valid uninitialized link
+        let mut link: MumbleLink = unsafe { mem::zeroed() };
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut watcher = MumbleWatcher::new();
+
+        assert!(watcher.poll(&link, at(0)).is_empty());
+        assert!(watcher.is_stale());
+
+        link.ui_tick = 1;
+        link.context.map_id = 15;
+        assert_eq!(
+            watcher.poll(&link, at(0)),
+            [MumbleChange::Stale { is_stale: false }]
+        );
+
+        link.ui_tick = 2;
+        link.context.map_id = 50;
+        assert_eq!(
+            watcher.poll(&link, at(100)),
+            [MumbleChange::Map { from: 15, to: 50 }]
+        );
+
+        // ui_tick stuck
+        assert!(watcher.poll(&link, at(1000)).is_empty());
+        assert_eq!(
+            watcher.poll(&link, at(2100)),
+            [MumbleChange::Stale { is_stale: true }]
+        );
+        link.context.map_id = 15;
+        assert!(watcher.poll(&link, at(3000)).is_empty());
+        assert!(watcher.is_stale());
+
+        link.ui_tick = 3;
+        assert_eq!(
+            watcher.poll(&link, at(4000)),
+            [
+                MumbleChange::Stale { is_stale: false },
+                MumbleChange::Map { from: 50, to: 15 }
+            ]
+        );
+    }
+}
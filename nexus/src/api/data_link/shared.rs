@@ -0,0 +1,503 @@
+use crate::{util::str_to_c, AddonApi, DataLinkApi};
+use std::{collections::BTreeSet, error::Error, fmt, marker::PhantomData, mem, ptr, sync::Mutex};
+
+/// Magic bytes at the start of a [`SharedHeader`].
+pub const SHARED_MAGIC: [u8; 4] = *b"NXSR";
+
+/// Identifiers with a live [`SharedResourceHandle`].
+static PUBLISHED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// Header preceding the payload of a [`SharedResource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct SharedHeader {
+    /// Magic bytes, see [`SHARED_MAGIC`].
+    pub magic: [u8; 4],
+
+    /// Layout version of the payload.
+    pub version: u32,
+
+    /// Size of the payload in bytes.
+    pub size: u32,
+
+    /// Offset of the payload from the start of the header in bytes.
+    pub offset: u32,
+
+    /// Hash identifying the payload type.
+    pub type_hash: u64,
+}
+
+impl SharedHeader {
+    /// Validates the header against an expected header.
+    pub fn validate(&self, expected: &Self) -> Result<(), SharedResourceError> {
+        if self.magic != expected.magic {
+            Err(SharedResourceError::Magic { found: self.magic })
+        } else if self.version != expected.version {
+            Err(SharedResourceError::Version {
+                expected: expected.version,
+                found: self.version,
+            })
+        } else if self.size != expected.size {
+            Err(SharedResourceError::Size {
+                expected: expected.size,
+                found: self.size,
+            })
+        } else if self.offset != expected.offset {
+            Err(SharedResourceError::Offset {
+                expected: expected.offset,
+                found: self.offset,
+            })
+        } else if self.type_hash != expected.type_hash {
+            Err(SharedResourceError::Type {
+                expected: expected.type_hash,
+                found: self.type_hash,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Error when accessing a [`SharedResource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedResourceError {
+    /// Resource does not exist.
+    Missing,
+
+    /// Resource could not be allocated.
+    ShareFailed,
+
+    /// Resource is already published by a live handle.
+    AlreadyPublished,
+
+    /// Resource was not published as [`SharedResource`] or was revoked.
+    Magic { found: [u8; 4] },
+
+    /// Layout version differs.
+    Version { expected: u32, found: u32 },
+
+    /// Payload size differs.
+    Size { expected: u32, found: u32 },
+
+    /// Payload offset differs.
+    Offset { expected: u32, found: u32 },
+
+    /// Payload type differs.
+    Type { expected: u64, found: u64 },
+}
+
+impl fmt::Display for SharedResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "shared resource does not exist"),
+            Self::ShareFailed => write!(f, "failed to share resource"),
+            Self::AlreadyPublished => write!(f, "shared resource is already published"),
+            Self::Magic { found } => write!(f, "invalid shared resource magic {found:02x?}"),
+            Self::Version { expected, found } => {
+                write!(f, "expected layout version {expected}, found {found}")
+            }
+            Self::Size { expected, found } => {
+                write!(f, "expected payload size {expected}, found {found}")
+            }
+            Self::Offset { expected, found } => {
+                write!(f, "expected payload offset {expected}, found {found}")
+            }
+            Self::Type { expected, found } => {
+                write!(f, "expected type hash {expected:016x}, found {found:016x}")
+            }
+        }
+    }
+}
+
+impl Error for SharedResourceError {}
+
+/// A typed & versioned shared resource.
+///
+/// The payload is preceded by a [`SharedHeader`], which is validated on every read.
+/// This protects readers from layout changes in the publishing addon.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::data_link::SharedResource;
+///
+/// #[derive(Debug, Clone, Copy)]
+/// #[repr(C)]
+/// struct MyData {
+///     counter: u32,
+/// }
+///
+/// const MY_DATA: SharedResource<MyData> = unsafe { SharedResource::new("DL_MY_DATA", 1, "MyData") };
+///
+/// let mut handle = MY_DATA.publish(MyData { counter: 0 }).unwrap();
+/// handle.update(|data| data.counter += 1);
+///
+/// match MY_DATA.read() {
+///     Ok(data) => println!("{data:?}"),
+///     Err(err) => println!("{err}"),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SharedResource<T> {
+    /// Data link identifier.
+    pub identifier: &'static str,
+
+    /// Layout version of the payload.
+    pub version: u32,
+
+    /// Name identifying the payload type.
+    pub type_name: &'static str,
+
+    _phantom: PhantomData<T>,
+}
+
+impl<T> SharedResource<T> {
+    /// Creates a new shared resource with the given identifier, layout version & type name.
+    ///
+    /// The type name is hashed to identify the payload type.
+    /// Addons sharing the resource have to use the same name, independent of their Rust type paths.
+    ///
+    /// # Safety
+    /// The type must be plain data with a stable layout, like `#[repr(C)]` structs without pointers to addon memory.
+    /// All addons using the identifier with the same version, size & type hash must agree on the layout.
+    #[inline]
+    pub const unsafe fn new(
+        identifier: &'static str,
+        version: u32,
+        type_name: &'static str,
+    ) -> Self {
+        Self {
+            identifier,
+            version,
+            type_name,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the hash identifying the payload type.
+    #[inline]
+    pub const fn type_hash(&self) -> u64 {
+        type_hash(self.type_name)
+    }
+
+    /// Returns the offset of the payload from the start of the header.
+    #[inline]
+    pub const fn payload_offset() -> usize {
+        let align = mem::align_of::<T>();
+        mem::size_of::<SharedHeader>().div_ceil(align) * align
+    }
+
+    /// Returns the expected header.
+    #[inline]
+    pub const fn header(&self) -> SharedHeader {
+        SharedHeader {
+            magic: SHARED_MAGIC,
+            version: self.version,
+            size: mem::size_of::<T>() as u32,
+            offset: Self::payload_offset() as u32,
+            type_hash: self.type_hash(),
+        }
+    }
+
+    /// Reads the header currently in shared memory.
+    pub fn read_header(&self) -> Result<SharedHeader, SharedResourceError> {
+        let ptr = super::get_resource::<SharedHeader>(self.identifier);
+        if ptr.is_null() {
+            Err(SharedResourceError::Missing)
+        } else {
+            Ok(unsafe { ptr.read_volatile() })
+        }
+    }
+
+    /// Returns a pointer to the payload after validating the header.
+    pub fn get(&self) -> Result<*const T, SharedResourceError> {
+        let ptr = super::get_resource::<u8>(self.identifier);
+        if ptr.is_null() {
+            return Err(SharedResourceError::Missing);
+        }
+        let header = unsafe { ptr.cast::<SharedHeader>().read_volatile() };
+        header.validate(&self.header())?;
+        Ok(unsafe { ptr.add(Self::payload_offset()) }.cast())
+    }
+
+    /// Reads the payload after validating the header.
+    #[inline]
+    pub fn read(&self) -> Result<T, SharedResourceError> {
+        self.get().map(|ptr| unsafe { ptr.read_volatile() })
+    }
+
+    /// Publishes the resource with the given initial value.
+    ///
+    /// An existing resource, published before or by another addon, is only overwritten if its header matches.
+    /// Revoked resources with a matching header can be published again.
+    ///
+    /// Returns a [`SharedResourceHandle`] controlling the payload.
+    /// Only a single handle per identifier can be alive at a time.
+    pub fn publish(&self, value: T) -> Result<SharedResourceHandle<T>, SharedResourceError> {
+        if !PUBLISHED.lock().unwrap().insert(self.identifier) {
+            return Err(SharedResourceError::AlreadyPublished);
+        }
+        if let Err(err) = self.check_existing() {
+            PUBLISHED.lock().unwrap().remove(self.identifier);
+            return Err(err);
+        }
+        let identifier = str_to_c(self.identifier, "failed to convert data link identifier");
+        let DataLinkApi { share, .. } = AddonApi::get().data_link;
+        let size = Self::payload_offset() + mem::size_of::<T>();
+        let ptr = unsafe { share(identifier.as_ptr(), size) }.cast::<u8>();
+        if ptr.is_null() {
+            PUBLISHED.lock().unwrap().remove(self.identifier);
+            return Err(SharedResourceError::ShareFailed);
+        }
+
+        let mut handle = SharedResourceHandle {
+            identifier: self.identifier,
+            ptr,
+            _phantom: PhantomData,
+        };
+        handle.set(value);
+        unsafe { ptr.cast::<SharedHeader>().write_volatile(self.header()) };
+        Ok(handle)
+    }
+
+    /// Validates the header of an existing resource.
+    ///
+    /// Revoked headers are validated ignoring their cleared magic.
+    fn check_existing(&self) -> Result<(), SharedResourceError> {
+        let header = match self.read_header() {
+            Ok(header) => header,
+            Err(SharedResourceError::Missing) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let header = if header.magic == [0; 4] {
+            SharedHeader {
+                magic: SHARED_MAGIC,
+                ..header
+            }
+        } else {
+            header
+        };
+        header.validate(&self.header())
+    }
+}
+
+/// Handle controlling the payload of a published [`SharedResource`].
+///
+/// Writing requires exclusive access to the handle.
+/// Wrap it in a [`Mutex`] to write from multiple threads.
+/// Dropping the handle allows publishing the identifier again, the data stays shared.
+#[derive(Debug)]
+pub struct SharedResourceHandle<T> {
+    identifier: &'static str,
+    ptr: *mut u8,
+    _phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for SharedResourceHandle<T> {}
+
+impl<T> SharedResourceHandle<T> {
+    /// Returns the data link identifier.
+    #[inline]
+    pub fn identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    /// Returns a pointer to the header.
+    #[inline]
+    pub fn header_ptr(&self) -> *mut SharedHeader {
+        self.ptr.cast()
+    }
+
+    /// Returns a pointer to the payload.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        unsafe { self.ptr.add(SharedResource::<T>::payload_offset()) }.cast()
+    }
+
+    /// Reads the current payload.
+    #[inline]
+    pub fn read(&self) -> T {
+        unsafe { self.as_ptr().read_volatile() }
+    }
+
+    /// Overwrites the payload.
+    #[inline]
+    pub fn set(&mut self, value: T) {
+        unsafe { self.as_ptr().write_volatile(value) }
+    }
+
+    /// Updates the payload in place.
+    #[inline]
+    pub fn update(&mut self, update: impl FnOnce(&mut T)) {
+        let mut value = self.read();
+        update(&mut value);
+        self.set(value);
+    }
+
+    /// Revokes the resource.
+    ///
+    /// The header magic is cleared, so readers fail with [`SharedResourceError::Magic`].
+    /// Nexus does not free shared resources, the memory stays allocated.
+    pub fn revoke(self) {
+        let header = self.header_ptr();
+        unsafe { ptr::addr_of_mut!((*header).magic).write_volatile([0; 4]) }
+    }
+}
+
+impl<T> Drop for SharedResourceHandle<T> {
+    fn drop(&mut self) {
+        PUBLISHED.lock().unwrap().remove(self.identifier);
+    }
+}
+
+/// Computes a stable hash of a type name.
+///
+/// This uses 64-bit FNV-1a to be independent of the compiler version.
+pub const fn type_hash(type_name: &str) -> u64 {
+    let bytes = type_name.as_bytes();
+    let mut hash = 0xcbf29ce484222325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_validation() {
+        let resource = unsafe { SharedResource::<[u64; 2]>::new("DL_TEST", 2, "Test") };
+        let expected = resource.header();
+        assert_eq!(SharedResource::<[u64; 2]>::payload_offset(), 24);
+        assert_eq!(expected.validate(&expected), Ok(()));
+
+        let renamed = SharedResource {
+            type_name: "Other",
+            ..resource
+        };
+        assert_ne!(renamed.type_hash(), resource.type_hash());
+        assert!(matches!(
+            renamed.header().validate(&expected),
+            Err(SharedResourceError::Type { .. })
+        ));
+
+        let moved = SharedHeader {
+            offset: 32,
+            ..expected
+        };
+        assert_eq!(
+            moved.validate(&expected),
+            Err(SharedResourceError::Offset {
+                expected: 24,
+                found: 32
+            })
+        );
+
+        let older = SharedHeader {
+            version: 1,
+            ..expected
+        };
+        assert_eq!(
+            older.validate(&expected),
+            Err(SharedResourceError::Version {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let revoked = SharedHeader {
+            magic: [0; 4],
+            ..expected
+        };
+        assert_eq!(
+            revoked.validate(&expected),
+            Err(SharedResourceError::Magic { found: [0; 4] })
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn publish_read_revoke() {
+        use crate::recording::test_api;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[repr(C)]
+        struct Data {
+            counter: u32,
+            value: f32,
+        }
+
+        const DATA: SharedResource<Data> =
+            unsafe { SharedResource::new("DL_SHARED_TEST", 1, "Data") };
+        const NEWER: SharedResource<Data> =
+            unsafe { SharedResource::new("DL_SHARED_TEST", 2, "Data") };
+        const LARGER: SharedResource<[Data; 2]> =
+            unsafe { SharedResource::new("DL_SHARED_TEST", 1, "Data") };
+
+        assert!(test_api::install());
+        assert_eq!(DATA.read(), Err(SharedResourceError::Missing));
+
+        let mut handle = DATA
+            .publish(Data {
+                counter: 0,
+                value: 1.5,
+            })
+            .unwrap();
+        assert_eq!(
+            DATA.publish(Data {
+                counter: 0,
+                value: 0.0
+            })
+            .err(),
+            Some(SharedResourceError::AlreadyPublished)
+        );
+        handle.update(|data| data.counter += 1);
+        assert_eq!(
+            DATA.read(),
+            Ok(Data {
+                counter: 1,
+                value: 1.5
+            })
+        );
+        assert_eq!(
+            NEWER.read(),
+            Err(SharedResourceError::Version {
+                expected: 2,
+                found: 1
+            })
+        );
+
+        handle.revoke();
+        assert_eq!(
+            DATA.read(),
+            Err(SharedResourceError::Magic { found: [0; 4] })
+        );
+
+        // existing resources with a different layout are not overwritten
+        let data = Data {
+            counter: 5,
+            value: 0.0,
+        };
+        assert!(matches!(
+            LARGER.publish([data; 2]),
+            Err(SharedResourceError::Size { .. })
+        ));
+        assert!(matches!(
+            NEWER.publish(data),
+            Err(SharedResourceError::Version {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert_eq!(DATA.read_header().unwrap().version, 1);
+
+        // revoked resources with the same layout can be published again
+        let handle = DATA.publish(data).unwrap();
+        assert_eq!(DATA.read(), Ok(data));
+        drop(handle);
+    }
+}
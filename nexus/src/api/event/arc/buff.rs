@@ -0,0 +1,372 @@
+//! Buff & boon uptime tracking over ArcDPS combat events.
+//!
+//! Stacks are counted from buff applications and removals.
+//! Buffs stacking in duration count at most one effective stack.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::event::{arc::{buff::{BuffTracker, boon}, CombatData, COMBAT_SQUAD}, event_consume};
+//! use std::sync::Mutex;
+//!
+//! static BUFFS: Mutex<BuffTracker> = Mutex::new(BuffTracker::new());
+//!
+//! BUFFS.lock().unwrap().add_boons();
+//!
+//! COMBAT_SQUAD
+//!     .subscribe(event_consume!(<CombatData> |data| {
+//!         if let Some(data) = data {
+//!             BUFFS.lock().unwrap().process(data);
+//!         }
+//!     }))
+//!     .revert_on_unload();
+//!
+//! # let (agent, start, end) = (0, 0, 0);
+//! let might = BUFFS.lock().unwrap().average_stacks(agent, boon::MIGHT, start, end);
+//! ```
+
+use super::CombatData;
+use arcdps::evtc::{BuffRemove, Event, StateChange};
+use std::collections::BTreeMap;
+
+/// Skill ids of boons.
+pub mod boon {
+    pub const PROTECTION: u32 = 717;
+    pub const REGENERATION: u32 = 718;
+    pub const SWIFTNESS: u32 = 719;
+    pub const FURY: u32 = 725;
+    pub const VIGOR: u32 = 726;
+    pub const MIGHT: u32 = 740;
+    pub const AEGIS: u32 = 743;
+    pub const RESOLUTION: u32 = 873;
+    pub const STABILITY: u32 = 1122;
+    pub const QUICKNESS: u32 = 1187;
+    pub const RESISTANCE: u32 = 26980;
+    pub const ALACRITY: u32 = 30328;
+}
+
+/// Stacking behavior of a buff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum Stacking {
+    /// Every stack is active at the same time.
+    Intensity,
+
+    /// Stacks are queued and only one is active at a time.
+    Duration,
+}
+
+impl Stacking {
+    /// Returns the number of effective stacks for the given number of stacks.
+    #[inline]
+    pub fn effective(&self, stacks: u32) -> u32 {
+        match self {
+            Self::Intensity => stacks,
+            Self::Duration => stacks.min(1),
+        }
+    }
+}
+
+/// Stack timeline of a buff on an agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuffTimeline {
+    /// Stacking behavior of the buff.
+    pub stacking: Stacking,
+
+    /// Current number of stacks.
+    pub stacks: u32,
+
+    /// Changes in effective stacks as time & stacks pairs.
+    pub changes: Vec<(u64, u32)>,
+}
+
+impl BuffTimeline {
+    /// Creates a new empty timeline.
+    #[inline]
+    pub const fn new(stacking: Stacking) -> Self {
+        Self {
+            stacking,
+            stacks: 0,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Returns the number of effective stacks at the given time.
+    pub fn stacks_at(&self, time: u64) -> u32 {
+        let index = self.changes.partition_point(|(change, _)| *change <= time);
+        index
+            .checked_sub(1)
+            .map(|index| self.changes[index].1)
+            .unwrap_or(0)
+    }
+
+    /// Returns the fraction of the time window with at least one stack.
+    #[inline]
+    pub fn uptime(&self, start: u64, end: u64) -> f64 {
+        self.integrate(start, end, |stacks| stacks.min(1))
+    }
+
+    /// Returns the average number of effective stacks over the time window.
+    #[inline]
+    pub fn average_stacks(&self, start: u64, end: u64) -> f64 {
+        self.integrate(start, end, |stacks| stacks)
+    }
+
+    fn set(&mut self, time: u64, stacks: u32) {
+        self.stacks = stacks;
+        let effective = self.stacking.effective(stacks);
+        match self.changes.last_mut() {
+            Some(last) if last.0 == time => last.1 = effective,
+            Some(last) if last.1 == effective => {}
+            _ => self.changes.push((time, effective)),
+        }
+    }
+
+    fn integrate(&self, start: u64, end: u64, map: impl Fn(u32) -> u32) -> f64 {
+        if end <= start {
+            return 0.0;
+        }
+        let mut total = 0u64;
+        let mut time = start;
+        let mut stacks = self.stacks_at(start);
+        for (change, next) in &self.changes {
+            if *change <= start {
+                continue;
+            }
+            if *change >= end {
+                break;
+            }
+            total += (change - time) * map(stacks) as u64;
+            time = *change;
+            stacks = *next;
+        }
+        total += (end - time) * map(stacks) as u64;
+        total as f64 / (end - start) as f64
+    }
+}
+
+/// Tracks buff stacks per agent from ArcDPS combat events.
+#[derive(Debug, Clone, Default)]
+pub struct BuffTracker {
+    stacking: BTreeMap<u32, Stacking>,
+    timelines: BTreeMap<(u64, u32), BuffTimeline>,
+}
+
+impl BuffTracker {
+    /// Creates a new tracker without known buffs.
+    ///
+    /// Unknown buffs are assumed to stack in intensity.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            stacking: BTreeMap::new(),
+            timelines: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the stacking behavior of a buff.
+    #[inline]
+    pub fn set_stacking(&mut self, buff: u32, stacking: Stacking) {
+        self.stacking.insert(buff, stacking);
+    }
+
+    /// Sets the stacking behavior of all [boons](boon).
+    pub fn add_boons(&mut self) {
+        for buff in [boon::MIGHT, boon::STABILITY] {
+            self.set_stacking(buff, Stacking::Intensity);
+        }
+        for buff in [
+            boon::PROTECTION,
+            boon::REGENERATION,
+            boon::SWIFTNESS,
+            boon::FURY,
+            boon::VIGOR,
+            boon::AEGIS,
+            boon::RESOLUTION,
+            boon::QUICKNESS,
+            boon::RESISTANCE,
+            boon::ALACRITY,
+        ] {
+            self.set_stacking(buff, Stacking::Duration);
+        }
+    }
+
+    /// Returns the stacking behavior of a buff.
+    #[inline]
+    pub fn stacking(&self, buff: u32) -> Stacking {
+        self.stacking
+            .get(&buff)
+            .copied()
+            .unwrap_or(Stacking::Intensity)
+    }
+
+    /// Processes the [`CombatData`] of a combat event.
+    #[inline]
+    pub fn process(&mut self, data: &CombatData) {
+        if let Some(event) = data.event() {
+            self.process_event(event);
+        }
+    }
+
+    /// Processes an [`Event`].
+    pub fn process_event(&mut self, event: &Event) {
+        match event.get_statechange() {
+            StateChange::BuffInitial => self.apply(event),
+            StateChange::None if event.buff != 0 && event.is_activation == 0 => {
+                match event.get_buffremove() {
+                    BuffRemove::None if event.value != 0 && event.is_offcycle == 0 => {
+                        self.apply(event)
+                    }
+                    BuffRemove::All => {
+                        self.update(event.src_agent, event.skill_id, event.time, |_| 0)
+                    }
+                    BuffRemove::Single | BuffRemove::Manual => {
+                        self.update(event.src_agent, event.skill_id, event.time, |stacks| {
+                            stacks.saturating_sub(1)
+                        })
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply(&mut self, event: &Event) {
+        self.update(event.dst_agent, event.skill_id, event.time, |stacks| {
+            stacks + 1
+        });
+    }
+
+    fn update(&mut self, agent: u64, buff: u32, time: u64, stacks: impl FnOnce(u32) -> u32) {
+        let stacking = self.stacking(buff);
+        let timeline = self
+            .timelines
+            .entry((agent, buff))
+            .or_insert_with(|| BuffTimeline::new(stacking));
+        timeline.set(time, stacks(timeline.stacks));
+    }
+
+    /// Returns the stack timeline of a buff on an agent.
+    #[inline]
+    pub fn timeline(&self, agent: u64, buff: u32) -> Option<&BuffTimeline> {
+        self.timelines.get(&(agent, buff))
+    }
+
+    /// Returns all tracked stack timelines by agent & buff.
+    #[inline]
+    pub fn timelines(&self) -> impl Iterator<Item = (u64, u32, &BuffTimeline)> {
+        self.timelines
+            .iter()
+            .map(|((agent, buff), timeline)| (*agent, *buff, timeline))
+    }
+
+    /// Returns the uptime of a buff on an agent over the time window as fraction.
+    #[inline]
+    pub fn uptime(&self, agent: u64, buff: u32, start: u64, end: u64) -> f64 {
+        self.timeline(agent, buff)
+            .map(|timeline| timeline.uptime(start, end))
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the average number of effective stacks of a buff on an agent over the time window.
+    #[inline]
+    pub fn average_stacks(&self, agent: u64, buff: u32, start: u64, end: u64) -> f64 {
+        self.timeline(agent, buff)
+            .map(|timeline| timeline.average_stacks(start, end))
+            .unwrap_or(0.0)
+    }
+
+    /// Clears all tracked timelines.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.timelines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(time: u64, agent: u64, buff: u32) -> Event {
+        Event {
+            time,
+            dst_agent: agent,
+            skill_id: buff,
+            buff: 1,
+            value: 5000,
+            ..Event::default()
+        }
+    }
+
+    fn remove(time: u64, agent: u64, buff: u32, kind: BuffRemove) -> Event {
+        Event {
+            time,
+            src_agent: agent,
+            skill_id: buff,
+            buff: 1,
+            is_buffremove: kind.into(),
+            ..Event::default()
+        }
+    }
+
+    #[test]
+    fn intensity_and_duration() {
+        let mut tracker = BuffTracker::new();
+        tracker.add_boons();
+
+        // 2 might stacks for 1s, 1 stack for 1s
+        tracker.process_event(&apply(1000, 1, boon::MIGHT));
+        tracker.process_event(&apply(1000, 1, boon::MIGHT));
+        tracker.process_event(&remove(2000, 1, boon::MIGHT, BuffRemove::Single));
+        tracker.process_event(&remove(3000, 1, boon::MIGHT, BuffRemove::All));
+
+        // 2 queued quickness stacks for 1s
+        tracker.process_event(&apply(1000, 1, boon::QUICKNESS));
+        tracker.process_event(&apply(1500, 1, boon::QUICKNESS));
+        tracker.process_event(&remove(2000, 1, boon::QUICKNESS, BuffRemove::All));
+
+        assert_eq!(tracker.uptime(1, boon::MIGHT, 1000, 5000), 0.5);
+        assert_eq!(tracker.average_stacks(1, boon::MIGHT, 1000, 3000), 1.5);
+        assert_eq!(tracker.uptime(1, boon::QUICKNESS, 0, 2000), 0.5);
+        assert_eq!(tracker.average_stacks(1, boon::QUICKNESS, 1000, 2000), 1.0);
+        assert_eq!(tracker.uptime(2, boon::MIGHT, 1000, 5000), 0.0);
+    }
+
+    #[test]
+    fn removals() {
+        let mut tracker = BuffTracker::new();
+        for _ in 0..5 {
+            tracker.process_event(&apply(1000, 1, boon::MIGHT));
+        }
+        let stacks =
+            |tracker: &BuffTracker, time| tracker.timeline(1, boon::MIGHT).unwrap().stacks_at(time);
+        assert_eq!(stacks(&tracker, 1000), 5);
+
+        tracker.process_event(&remove(2000, 1, boon::MIGHT, BuffRemove::Single));
+        assert_eq!(stacks(&tracker, 2000), 4);
+
+        // manual & out of combat removal drop a single stack
+        tracker.process_event(&remove(3000, 1, boon::MIGHT, BuffRemove::Manual));
+        assert_eq!(stacks(&tracker, 3000), 3);
+
+        tracker.process_event(&remove(4000, 1, boon::MIGHT, BuffRemove::All));
+        assert_eq!(stacks(&tracker, 4000), 0);
+
+        // single removal without stacks does not underflow
+        tracker.process_event(&remove(5000, 1, boon::MIGHT, BuffRemove::Single));
+        assert_eq!(stacks(&tracker, 5000), 0);
+    }
+}
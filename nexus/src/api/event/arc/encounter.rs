@@ -0,0 +1,486 @@
+//! Encounter detection from the ArcDPS combat stream.
+//!
+//! An encounter starts on a log start or the first hit on a tracked species.
+//! It ends on a log end, the death of the boss or when all squad members left combat.
+//! The boss is the agent hit first, agents sharing its species like split phase copies do not end the encounter.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::event::{
+//!     arc::{encounter::{EncounterEvent, EncounterTracker}, CombatData, COMBAT_SQUAD},
+//!     event_consume,
+//! };
+//! use std::sync::Mutex;
+//!
+//! static TRACKER: Mutex<EncounterTracker> = Mutex::new(EncounterTracker::new());
+//!
+//! TRACKER.lock().unwrap().track_species([15438, 15429]);
+//!
+//! COMBAT_SQUAD
+//!     .subscribe(event_consume!(<CombatData> |data| {
+//!         if let Some(data) = data {
+//!             match TRACKER.lock().unwrap().process(data) {
+//!                 Some(EncounterEvent::Started(started)) => {}
+//!                 Some(EncounterEvent::Ended(ended)) => {}
+//!                 None => {}
+//!             }
+//!         }
+//!     }))
+//!     .revert_on_unload();
+//! ```
+
+use super::{stats::Hit, AgentUpdate, CombatData};
+use arcdps::evtc::{Agent, Event, StateChange};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Kind of an agent seen in the combat stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AgentKind {
+    /// Player character.
+    Player,
+
+    /// Non-player character with species id.
+    Npc(u32),
+
+    /// Gadget.
+    Gadget,
+}
+
+impl AgentKind {
+    /// Determines the kind of the given [`Agent`].
+    #[inline]
+    pub fn from_agent(agent: &Agent) -> Self {
+        Self::from_prof_elite(agent.prof, agent.elite)
+    }
+
+    /// Determines the kind from raw profession & elite values.
+    #[inline]
+    pub fn from_prof_elite(prof: u32, elite: u32) -> Self {
+        if elite != u32::MAX {
+            Self::Player
+        } else if prof >> 16 == 0xffff {
+            Self::Gadget
+        } else {
+            Self::Npc(prof)
+        }
+    }
+
+    /// Returns the species id, if the agent is a non-player character.
+    #[inline]
+    pub fn species(&self) -> Option<u32> {
+        match self {
+            Self::Npc(species) => Some(*species),
+            _ => None,
+        }
+    }
+}
+
+/// Squad member taking part in an encounter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SquadMember {
+    /// ArcDPS id of the agent.
+    pub id: u64,
+
+    /// Account name.
+    pub account: String,
+
+    /// Character name.
+    pub character: String,
+
+    /// Profession.
+    pub profession: u32,
+
+    /// Elite specialization.
+    pub elite: u32,
+
+    /// Subgroup.
+    pub subgroup: u16,
+}
+
+impl From<&AgentUpdate> for SquadMember {
+    #[inline]
+    fn from(agent: &AgentUpdate) -> Self {
+        Self {
+            id: agent.id as u64,
+            account: agent.account().to_string_lossy().into_owned(),
+            character: agent.character().to_string_lossy().into_owned(),
+            profession: agent.prof,
+            elite: agent.elite,
+            subgroup: agent.subgroup,
+        }
+    }
+}
+
+/// Notification about an encounter emitted by [`EncounterTracker`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EncounterEvent {
+    /// An encounter has started.
+    Started(EncounterStarted),
+
+    /// An encounter has ended.
+    Ended(EncounterEnded),
+}
+
+/// Notification about a started encounter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncounterStarted {
+    /// Species id of the boss.
+    pub species_id: u32,
+
+    /// Start time in milliseconds.
+    pub time: u64,
+
+    /// Squad composition at encounter start.
+    pub squad: Vec<SquadMember>,
+}
+
+/// Notification about an ended encounter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncounterEnded {
+    /// Species id of the boss.
+    pub species_id: u32,
+
+    /// Start time in milliseconds.
+    pub start: u64,
+
+    /// End time in milliseconds.
+    pub end: u64,
+
+    /// Whether the boss died.
+    pub is_kill: bool,
+
+    /// Squad composition at encounter start.
+    pub squad: Vec<SquadMember>,
+}
+
+impl EncounterEnded {
+    /// Returns the duration of the encounter in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Currently running encounter.
+#[derive(Debug, Clone)]
+struct Running {
+    species_id: u32,
+    boss: Option<u64>,
+    start: u64,
+    squad: Vec<SquadMember>,
+}
+
+/// Detects encounter start & end from ArcDPS combat events.
+#[derive(Debug, Clone, Default)]
+pub struct EncounterTracker {
+    species: BTreeSet<u32>,
+    agents: BTreeMap<u64, AgentKind>,
+    squad: BTreeMap<u64, SquadMember>,
+    in_combat: BTreeSet<u64>,
+    current: Option<Running>,
+}
+
+impl EncounterTracker {
+    /// Creates a new tracker without tracked species.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            species: BTreeSet::new(),
+            agents: BTreeMap::new(),
+            squad: BTreeMap::new(),
+            in_combat: BTreeSet::new(),
+            current: None,
+        }
+    }
+
+    /// Adds species ids to start encounters on first hit.
+    #[inline]
+    pub fn track_species(&mut self, species: impl IntoIterator<Item = u32>) {
+        self.species.extend(species);
+    }
+
+    /// Returns the species id of the running encounter.
+    #[inline]
+    pub fn current_species(&self) -> Option<u32> {
+        self.current.as_ref().map(|current| current.species_id)
+    }
+
+    /// Checks whether an encounter is running.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Returns the current squad members.
+    #[inline]
+    pub fn squad(&self) -> impl Iterator<Item = &SquadMember> {
+        self.squad.values()
+    }
+
+    /// Adds a squad member.
+    #[inline]
+    pub fn add_member(&mut self, member: SquadMember) {
+        self.agents.insert(member.id, AgentKind::Player);
+        self.squad.insert(member.id, member);
+    }
+
+    /// Removes a squad member.
+    #[inline]
+    pub fn remove_member(&mut self, id: u64) {
+        self.squad.remove(&id);
+        self.in_combat.remove(&id);
+    }
+
+    /// Processes an [`AgentUpdate`] from [`SELF_JOIN`](super::SELF_JOIN), [`SQUAD_JOIN`](super::SQUAD_JOIN) or [`SQUAD_LEAVE`](super::SQUAD_LEAVE).
+    #[inline]
+    pub fn process_agent_update(&mut self, agent: &AgentUpdate) {
+        if agent.is_added() {
+            self.add_member(agent.into());
+        } else {
+            self.remove_member(agent.id as u64);
+        }
+    }
+
+    /// Remembers the kind of an agent.
+    #[inline]
+    pub fn add_agent(&mut self, id: u64, kind: AgentKind) {
+        self.agents.insert(id, kind);
+    }
+
+    /// Processes the [`CombatData`] of a combat event.
+    pub fn process(&mut self, data: &CombatData) -> Option<EncounterEvent> {
+        for agent in [data.src(), data.dst()].into_iter().flatten() {
+            self.add_agent(agent.id as u64, AgentKind::from_agent(agent));
+        }
+        data.event().and_then(|event| self.process_event(event))
+    }
+
+    /// Processes an [`Event`].
+    ///
+    /// Agent kinds have to be known beforehand for first hit detection.
+    pub fn process_event(&mut self, event: &Event) -> Option<EncounterEvent> {
+        match event.get_statechange() {
+            StateChange::LogStart => {
+                let species_id = event.src_agent as u32;
+                self.start(species_id, None, event.time)
+            }
+            StateChange::LogEnd => self.end(event.time, false),
+            StateChange::EnterCombat => {
+                if self.is_player(event.src_agent) {
+                    self.in_combat.insert(event.src_agent);
+                }
+                None
+            }
+            StateChange::ExitCombat => {
+                let was_in_combat = self.in_combat.remove(&event.src_agent);
+                if was_in_combat && self.in_combat.is_empty() {
+                    self.end(event.time, false)
+                } else {
+                    None
+                }
+            }
+            StateChange::ChangeDead => {
+                let species = self
+                    .agents
+                    .get(&event.src_agent)
+                    .and_then(AgentKind::species);
+                let is_boss = self
+                    .current
+                    .as_ref()
+                    .is_some_and(|current| match current.boss {
+                        Some(boss) => boss == event.src_agent,
+                        None => species == Some(current.species_id),
+                    });
+                if is_boss {
+                    self.end(event.time, true)
+                } else {
+                    None
+                }
+            }
+            StateChange::None
+                if self
+                    .current
+                    .as_ref()
+                    .is_some_and(|current| current.boss.is_none()) =>
+            {
+                // first hit on the encounter species determines the boss agent
+                let hit = Hit::from_event(event).filter(Hit::is_damage)?;
+                let species_id = self.agents.get(&hit.dst)?.species()?;
+                let current = self.current.as_mut()?;
+                if species_id == current.species_id {
+                    current.boss = Some(hit.dst);
+                }
+                None
+            }
+            StateChange::None if self.current.is_none() => {
+                let hit = Hit::from_event(event).filter(Hit::is_damage)?;
+                let species_id = self.agents.get(&hit.dst)?.species()?;
+                self.species
+                    .contains(&species_id)
+                    .then(|| self.start(species_id, Some(hit.dst), hit.time))
+                    .flatten()
+            }
+            _ => None,
+        }
+    }
+
+    /// Resets the tracker without emitting an end notification.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.agents.clear();
+        self.in_combat.clear();
+        self.current = None;
+    }
+
+    fn is_player(&self, id: u64) -> bool {
+        self.squad.contains_key(&id) || self.agents.get(&id) == Some(&AgentKind::Player)
+    }
+
+    fn start(&mut self, species_id: u32, boss: Option<u64>, time: u64) -> Option<EncounterEvent> {
+        if self.current.is_some() {
+            return None;
+        }
+        let squad: Vec<_> = self.squad.values().cloned().collect();
+        self.current = Some(Running {
+            species_id,
+            boss,
+            start: time,
+            squad: squad.clone(),
+        });
+        Some(EncounterEvent::Started(EncounterStarted {
+            species_id,
+            time,
+            squad,
+        }))
+    }
+
+    fn end(&mut self, time: u64, is_kill: bool) -> Option<EncounterEvent> {
+        let current = self.current.take()?;
+        Some(EncounterEvent::Ended(EncounterEnded {
+            species_id: current.species_id,
+            start: current.start,
+            end: time,
+            is_kill,
+            squad: current.squad,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOSS: u64 = 100;
+    const ADD: u64 = 101;
+    const SPECIES: u32 = 15438;
+
+    fn statechange(time: u64, statechange: StateChange, src: u64) -> Event {
+        Event {
+            time,
+            src_agent: src,
+            is_statechange: statechange.into(),
+            ..Event::default()
+        }
+    }
+
+    fn strike(time: u64, src: u64, dst: u64) -> Event {
+        Event {
+            time,
+            src_agent: src,
+            dst_agent: dst,
+            value: 1000,
+            skill_id: 5,
+            ..Event::default()
+        }
+    }
+
+    fn tracker() -> EncounterTracker {
+        let mut tracker = EncounterTracker::new();
+        tracker.track_species([SPECIES]);
+        tracker.add_member(SquadMember {
+            id: 1,
+            account: ":Player.1234".into(),
+            character: "Player".into(),
+            profession: 1,
+            elite: 0,
+            subgroup: 1,
+        });
+        tracker.add_agent(BOSS, AgentKind::Npc(SPECIES));
+        tracker.add_agent(ADD, AgentKind::Npc(SPECIES));
+        tracker
+    }
+
+    fn ended(event: Option<EncounterEvent>) -> EncounterEnded {
+        match event {
+            Some(EncounterEvent::Ended(ended)) => ended,
+            other => panic!("expected end, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn log_start_end() {
+        let mut tracker = tracker();
+        let started =
+            tracker.process_event(&statechange(1000, StateChange::LogStart, SPECIES as u64));
+        assert!(matches!(
+            started,
+            Some(EncounterEvent::Started(EncounterStarted { species_id: SPECIES, time: 1000, ref squad })) if squad.len() == 1
+        ));
+        assert_eq!(
+            tracker.process_event(&statechange(1500, StateChange::LogStart, 1)),
+            None
+        );
+
+        let ended = ended(tracker.process_event(&statechange(4000, StateChange::LogEnd, 0)));
+        assert_eq!(ended.duration(), 3000);
+        assert!(!ended.is_kill);
+        assert!(!tracker.is_running());
+    }
+
+    #[test]
+    fn first_hit_and_exit_combat() {
+        let mut tracker = tracker();
+        assert_eq!(
+            tracker.process_event(&statechange(500, StateChange::EnterCombat, 1)),
+            None
+        );
+        assert_eq!(tracker.process_event(&strike(800, 1, 200)), None);
+        assert!(tracker.process_event(&strike(1000, 1, BOSS)).is_some());
+        assert_eq!(tracker.current_species(), Some(SPECIES));
+
+        let ended = ended(tracker.process_event(&statechange(3000, StateChange::ExitCombat, 1)));
+        assert_eq!((ended.start, ended.end), (1000, 3000));
+        assert!(!ended.is_kill);
+    }
+
+    #[test]
+    fn boss_death_not_add() {
+        let mut tracker = tracker();
+        tracker.process_event(&strike(1000, 1, BOSS));
+        assert_eq!(
+            tracker.process_event(&statechange(2000, StateChange::ChangeDead, ADD)),
+            None
+        );
+        assert!(tracker.is_running());
+        assert!(
+            ended(tracker.process_event(&statechange(3000, StateChange::ChangeDead, BOSS))).is_kill
+        );
+
+        // boss determined by first hit after log start
+        tracker.process_event(&statechange(4000, StateChange::LogStart, SPECIES as u64));
+        tracker.process_event(&strike(4500, 1, ADD));
+        tracker.process_event(&strike(4600, 1, BOSS));
+        assert_eq!(
+            tracker.process_event(&statechange(5000, StateChange::ChangeDead, BOSS)),
+            None
+        );
+        assert!(
+            ended(tracker.process_event(&statechange(6000, StateChange::ChangeDead, ADD))).is_kill
+        );
+    }
+}
@@ -1,6 +1,13 @@
 //! [ArcDPS EVTC](https://deltaconnected.com/arcdps/) bridge events.
 
+pub mod buff;
+pub mod encounter;
+pub mod names;
+pub mod stats;
+pub mod writer;
+
 use super::Event;
+use crate::{game::impl_game_ids, util::str_from_c};
 use arcdps::evtc::{self, Agent};
 use std::ffi::{c_char, CStr};
 
@@ -65,9 +72,12 @@ pub const REPLAY_TARGET_CHANGED: Event<()> =
 /// ArcDPS player account name.
 ///
 /// Triggered on first map load.
-/// Can be triggered on demand by sending `"EV_REQUEST_ACCOUNT_NAME"`.
+/// Can be triggered on demand by sending [`REQUEST_ACCOUNT_NAME`].
 pub const ACCOUNT_NAME: Event<c_char> = unsafe { Event::new("EV_ACCOUNT_NAME") };
 
+/// Requests the [`ACCOUNT_NAME`] event.
+pub const REQUEST_ACCOUNT_NAME: Event<()> = unsafe { Event::new("EV_REQUEST_ACCOUNT_NAME") };
+
 /// ArcDPS agent update.
 #[derive(Debug, Clone)]
 #[repr(C)]
@@ -106,6 +116,8 @@ pub struct AgentUpdate {
     pub subgroup: u16,
 }
 
+impl_game_ids!(AgentUpdate { prof, elite });
+
 impl AgentUpdate {
     /// Returns the account name (if present).
     #[inline]
@@ -145,6 +157,7 @@ pub struct CombatData {
     event: *const evtc::Event,
     src: *const Agent,
     dst: *const Agent,
+    skill_name: *const c_char,
     pub id: u64,
     pub rev: u64,
 }
@@ -198,4 +211,16 @@ impl CombatData {
     pub fn dst(&self) -> Option<&Agent> {
         unsafe { self.dst.as_ref() }
     }
+
+    /// Returns a pointer to the skill name.
+    #[inline]
+    pub fn skill_name_ptr(&self) -> *const c_char {
+        self.skill_name
+    }
+
+    /// Returns the skill name.
+    #[inline]
+    pub fn skill_name(&self) -> Option<&str> {
+        unsafe { str_from_c(self.skill_name) }
+    }
 }
@@ -0,0 +1,341 @@
+//! Agent & skill name cache learned from ArcDPS events.
+//!
+//! Agent names are tied to the current map instance and cleared on [`SELF_JOIN`], which is triggered on map load.
+//! Skill names are kept across maps.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::event::arc::names::{agent_name, skill_name, subscribe_name_cache};
+//!
+//! subscribe_name_cache().revert_on_unload();
+//!
+//! let name = agent_name(1234);
+//! let skill = skill_name(5492);
+//! ```
+
+use super::{AgentUpdate, CombatData, COMBAT_LOCAL, COMBAT_SQUAD, SELF_JOIN, SQUAD_JOIN};
+use crate::{event::event_consume, util::str_from_c, Revertible};
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Shared name cache.
+static NAME_CACHE: Mutex<NameCache> = Mutex::new(NameCache::new());
+
+/// Names of an agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AgentName {
+    /// Character or NPC name.
+    pub name: String,
+
+    /// Account name for player characters.
+    pub account: Option<String>,
+}
+
+/// Cache of agent & skill names.
+#[derive(Debug, Clone, Default)]
+pub struct NameCache {
+    agents: BTreeMap<u64, AgentName>,
+    instances: BTreeMap<u16, u64>,
+    skills: BTreeMap<u32, String>,
+}
+
+impl NameCache {
+    /// Creates a new empty cache.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            agents: BTreeMap::new(),
+            instances: BTreeMap::new(),
+            skills: BTreeMap::new(),
+        }
+    }
+
+    /// Processes the [`CombatData`] of a combat event.
+    pub fn process(&mut self, data: &CombatData) {
+        match data.event() {
+            Some(event) => {
+                for (agent, instance_id) in [
+                    (data.src(), event.src_instance_id),
+                    (data.dst(), event.dst_instance_id),
+                ] {
+                    if let Some(agent) = agent {
+                        let id = agent.id as u64;
+                        if let Some(name) = unsafe { str_from_c(agent.name) } {
+                            self.set_agent_name(id, name);
+                        }
+                        if instance_id != 0 {
+                            self.instances.insert(instance_id, id);
+                        }
+                    }
+                }
+                if let Some(name) = data.skill_name() {
+                    self.set_skill_name(event.skill_id, name);
+                }
+            }
+            None => {
+                // agent notification, src has character name & dst has account name
+                if let (Some(src), Some(dst)) = (data.src(), data.dst()) {
+                    if src.elite == 0 {
+                        self.process_agent(
+                            src.id as u64,
+                            src.prof,
+                            dst.id,
+                            unsafe { str_from_c(src.name) },
+                            unsafe { str_from_c(dst.name) },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Processes an ArcDPS agent notification.
+    ///
+    /// Agents with a profession were added & have their names & instance id stored.
+    /// Agents without a profession were removed & have their instance id released, names are kept.
+    pub fn process_agent(
+        &mut self,
+        id: u64,
+        prof: u32,
+        instance_id: usize,
+        name: Option<&str>,
+        account: Option<&str>,
+    ) {
+        if prof != 0 {
+            if let Some(name) = name {
+                self.set_agent_name(id, name);
+            }
+            if let Some(account) = account {
+                self.set_agent_account(id, account);
+            }
+            if let Ok(instance_id) = instance_id.try_into() {
+                self.instances.insert(instance_id, id);
+            }
+        } else {
+            self.instances.retain(|_, agent| *agent != id);
+        }
+    }
+
+    /// Processes an [`AgentUpdate`].
+    pub fn process_agent_update(&mut self, agent: &AgentUpdate) {
+        let id = agent.id as u64;
+        self.set_agent_name(id, agent.character().to_string_lossy());
+        self.set_agent_account(id, agent.account().to_string_lossy());
+        if let Ok(instance_id) = agent.instance_id.try_into() {
+            self.instances.insert(instance_id, id);
+        }
+    }
+
+    /// Processes the [`AgentUpdate`] of a [`SELF_JOIN`].
+    ///
+    /// Agents of the previous map instance are cleared before the local player is added.
+    #[inline]
+    pub fn process_self_join(&mut self, agent: &AgentUpdate) {
+        self.clear_agents();
+        self.process_agent_update(agent);
+    }
+
+    /// Sets the name of an agent.
+    #[inline]
+    pub fn set_agent_name(&mut self, id: u64, name: impl AsRef<str>) {
+        let name = name.as_ref();
+        if !name.is_empty() {
+            self.agents.entry(id).or_default().name = name.to_owned();
+        }
+    }
+
+    /// Sets the account name of an agent.
+    #[inline]
+    pub fn set_agent_account(&mut self, id: u64, account: impl AsRef<str>) {
+        let account = account.as_ref();
+        if !account.is_empty() {
+            self.agents.entry(id).or_default().account = Some(account.to_owned());
+        }
+    }
+
+    /// Sets the name of a skill.
+    #[inline]
+    pub fn set_skill_name(&mut self, id: u32, name: impl AsRef<str>) {
+        let name = name.as_ref();
+        if id != 0 && !name.is_empty() {
+            self.skills.insert(id, name.to_owned());
+        }
+    }
+
+    /// Returns the names of an agent by ArcDPS id.
+    #[inline]
+    pub fn agent(&self, id: u64) -> Option<&AgentName> {
+        self.agents.get(&id)
+    }
+
+    /// Returns the name of an agent by ArcDPS id.
+    #[inline]
+    pub fn agent_name(&self, id: u64) -> Option<&str> {
+        self.agent(id)
+            .map(|agent| agent.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Returns the account name of an agent by ArcDPS id.
+    #[inline]
+    pub fn agent_account(&self, id: u64) -> Option<&str> {
+        self.agent(id).and_then(|agent| agent.account.as_deref())
+    }
+
+    /// Returns the ArcDPS id of an agent by instance id.
+    #[inline]
+    pub fn agent_id(&self, instance_id: u16) -> Option<u64> {
+        self.instances.get(&instance_id).copied()
+    }
+
+    /// Returns the name of an agent by instance id.
+    #[inline]
+    pub fn instance_name(&self, instance_id: u16) -> Option<&str> {
+        self.agent_id(instance_id)
+            .and_then(|id| self.agent_name(id))
+    }
+
+    /// Returns the name of a skill.
+    #[inline]
+    pub fn skill_name(&self, id: u32) -> Option<&str> {
+        self.skills.get(&id).map(String::as_str)
+    }
+
+    /// Clears agent names & instance ids, keeping skill names.
+    ///
+    /// Should be called on map change.
+    #[inline]
+    pub fn clear_agents(&mut self) {
+        self.agents.clear();
+        self.instances.clear();
+    }
+
+    /// Clears all names.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.clear_agents();
+        self.skills.clear();
+    }
+}
+
+/// Subscribes the shared [`NameCache`] to ArcDPS events.
+///
+/// Both local & squad combat events are processed, so names of squad members outside of the local area are known.
+///
+/// Returns a [`Revertible`] to revert the subscribes.
+pub fn subscribe_name_cache() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let local = COMBAT_LOCAL
+        .subscribe(event_consume!(<CombatData> |data| {
+            if let Some(data) = data {
+                with_name_cache(|cache| cache.process(data));
+            }
+        }))
+        .into_inner();
+    let squad = COMBAT_SQUAD
+        .subscribe(event_consume!(<CombatData> |data| {
+            if let Some(data) = data {
+                with_name_cache(|cache| cache.process(data));
+            }
+        }))
+        .into_inner();
+    let self_join = SELF_JOIN
+        .subscribe(event_consume!(<AgentUpdate> |agent| {
+            if let Some(agent) = agent {
+                with_name_cache(|cache| cache.process_self_join(agent));
+            }
+        }))
+        .into_inner();
+    let squad_join = SQUAD_JOIN
+        .subscribe(event_consume!(<AgentUpdate> |agent| {
+            if let Some(agent) = agent {
+                with_name_cache(|cache| cache.process_agent_update(agent));
+            }
+        }))
+        .into_inner();
+    let revert = move || {
+        local();
+        squad();
+        self_join();
+        squad_join();
+    };
+    revert.into()
+}
+
+/// Accesses the shared [`NameCache`].
+#[inline]
+pub fn with_name_cache<R>(body: impl FnOnce(&mut NameCache) -> R) -> R {
+    body(&mut NAME_CACHE.lock().unwrap())
+}
+
+/// Returns the name of an agent by ArcDPS id from the shared [`NameCache`].
+#[inline]
+pub fn agent_name(id: u64) -> Option<String> {
+    with_name_cache(|cache| cache.agent_name(id).map(ToOwned::to_owned))
+}
+
+/// Returns the account name of an agent by ArcDPS id from the shared [`NameCache`].
+#[inline]
+pub fn agent_account(id: u64) -> Option<String> {
+    with_name_cache(|cache| cache.agent_account(id).map(ToOwned::to_owned))
+}
+
+/// Returns the name of a skill from the shared [`NameCache`].
+#[inline]
+pub fn skill_name(id: u32) -> Option<String> {
+    with_name_cache(|cache| cache.skill_name(id).map(ToOwned::to_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::c_char;
+
+    fn agent_update(id: usize, instance_id: usize, character: &str, account: &str) -> AgentUpdate {
+        fn name(name: &str) -> [c_char; 64] {
+            let mut buffer = [0; 64];
+            for (dst, src) in buffer.iter_mut().zip(name.bytes()) {
+                *dst = src as c_char;
+            }
+            buffer
+        }
+
+        AgentUpdate {
+            account: name(account),
+            character: name(character),
+            id,
+            instance_id,
+            added: 1,
+            target: 0,
+            is_self: 1,
+            prof: 6,
+            elite: 0,
+            team: 0,
+            subgroup: 1,
+        }
+    }
+
+    #[test]
+    fn agent_added_and_removed() {
+        let mut cache = NameCache::new();
+        cache.set_skill_name(5492, "Fireball");
+        cache.process_agent(1, 6, 42, Some("Character"), Some(":Account.1234"));
+        assert_eq!(cache.agent_name(1), Some("Character"));
+        assert_eq!(cache.agent_account(1), Some(":Account.1234"));
+        assert_eq!(cache.instance_name(42), Some("Character"));
+
+        cache.process_agent(1, 0, 0, None, None);
+        assert_eq!(cache.agent_id(42), None);
+        assert_eq!(cache.agent_name(1), Some("Character"));
+
+        cache.process_agent(2, 6, 42, Some("Other"), None);
+        assert_eq!(cache.instance_name(42), Some("Other"));
+        assert_eq!(cache.agent_account(2), None);
+
+        cache.process_self_join(&agent_update(3, 7, "Self", ":Self.1234"));
+        assert_eq!(cache.agent_name(1), None);
+        assert_eq!(cache.agent_name(2), None);
+        assert_eq!(cache.instance_name(7), Some("Self"));
+        assert_eq!(cache.agent_account(3), Some(":Self.1234"));
+        assert_eq!(cache.skill_name(5492), Some("Fireball"));
+    }
+}
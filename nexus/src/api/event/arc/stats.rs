@@ -0,0 +1,574 @@
+//! Damage & healing aggregation over ArcDPS combat events.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::event::{arc::{stats::{CombatStats, StatsFilter}, CombatData, COMBAT_SQUAD}, event_consume};
+//! use std::sync::Mutex;
+//!
+//! static STATS: Mutex<CombatStats> = Mutex::new(CombatStats::new());
+//!
+//! COMBAT_SQUAD
+//!     .subscribe(event_consume!(<CombatData> |data| {
+//!         if let Some(data) = data {
+//!             STATS.lock().unwrap().process(data);
+//!         }
+//!     }))
+//!     .revert_on_unload();
+//!
+//! let stats = STATS.lock().unwrap().aggregate(&StatsFilter::new());
+//! for (id, source) in &stats.sources {
+//!     let dps = source.dps(stats.duration());
+//! }
+//! ```
+
+use super::CombatData;
+use arcdps::evtc::Event;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Strike result of a critical hit.
+const RESULT_CRIT: u8 = 1;
+
+/// Kind of a [`Hit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum HitKind {
+    /// Direct (strike) damage.
+    Power,
+
+    /// Buff (condition) damage tick.
+    Condition,
+
+    /// Healing reported by the ArcDPS healing extension.
+    Healing,
+}
+
+/// A single damage or healing hit extracted from an [`Event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hit {
+    /// Time of the hit in milliseconds.
+    pub time: u64,
+
+    /// ArcDPS id of the source agent.
+    pub src: u64,
+
+    /// ArcDPS id of the destination agent.
+    pub dst: u64,
+
+    /// Id of the skill causing the hit.
+    pub skill_id: u32,
+
+    /// Kind of the hit.
+    pub kind: HitKind,
+
+    /// Damage or healing amount.
+    pub amount: u64,
+
+    /// Whether the hit was a critical hit.
+    pub is_crit: bool,
+}
+
+impl Hit {
+    /// Extracts a hit from the given [`Event`].
+    ///
+    /// Returns [`None`] for state changes, activations, buff applications & removals and hits without damage.
+    pub fn from_event(event: &Event) -> Option<Self> {
+        if event.is_statechange != 0 || event.is_activation != 0 || event.is_buffremove != 0 {
+            return None;
+        }
+
+        let (kind, amount, is_crit) = if event.buff != 0 {
+            // buff apply has duration in value, buff damage has value 0
+            if event.value != 0 || event.result != 0 {
+                return None;
+            }
+            match event.buff_dmg {
+                dmg if dmg > 0 => (HitKind::Condition, dmg.unsigned_abs(), false),
+                dmg if dmg < 0 => (HitKind::Healing, dmg.unsigned_abs(), false),
+                _ => return None,
+            }
+        } else {
+            let is_crit = event.result == RESULT_CRIT;
+            match event.value {
+                value if value > 0 => (HitKind::Power, value.unsigned_abs(), is_crit),
+                value if value < 0 => (HitKind::Healing, value.unsigned_abs(), is_crit),
+                _ => return None,
+            }
+        };
+
+        Some(Self {
+            time: event.time,
+            src: event.src_agent,
+            dst: event.dst_agent,
+            skill_id: event.skill_id,
+            kind,
+            amount: amount.into(),
+            is_crit,
+        })
+    }
+
+    /// Checks whether the hit is damage.
+    #[inline]
+    pub fn is_damage(&self) -> bool {
+        matches!(self.kind, HitKind::Power | HitKind::Condition)
+    }
+}
+
+/// A named time window of an encounter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Phase {
+    /// Name of the phase.
+    pub name: String,
+
+    /// Start time in milliseconds.
+    pub start: u64,
+
+    /// End time in milliseconds.
+    pub end: u64,
+}
+
+impl Phase {
+    /// Creates a new phase.
+    #[inline]
+    pub fn new(name: impl Into<String>, start: u64, end: u64) -> Self {
+        Self {
+            name: name.into(),
+            start,
+            end,
+        }
+    }
+
+    /// Returns the duration of the phase in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Checks whether the given time is within the phase.
+    #[inline]
+    pub fn contains(&self, time: u64) -> bool {
+        (self.start..=self.end).contains(&time)
+    }
+}
+
+/// Filter for aggregating [`CombatStats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsFilter {
+    /// Time window to aggregate, defaults to the whole recording.
+    pub window: Option<(u64, u64)>,
+
+    /// ArcDPS ids of target agents to include, defaults to all targets.
+    pub targets: Option<BTreeSet<u64>>,
+
+    /// ArcDPS ids of source agents to include, defaults to all sources.
+    pub sources: Option<BTreeSet<u64>>,
+}
+
+impl StatsFilter {
+    /// Creates a new filter including everything.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the filter to the given time window.
+    #[inline]
+    pub fn window(mut self, start: u64, end: u64) -> Self {
+        self.window = Some((start, end));
+        self
+    }
+
+    /// Restricts the filter to the time window of the given [`Phase`].
+    #[inline]
+    pub fn phase(self, phase: &Phase) -> Self {
+        self.window(phase.start, phase.end)
+    }
+
+    /// Restricts the filter to the given target agents.
+    #[inline]
+    pub fn targets(mut self, targets: impl IntoIterator<Item = u64>) -> Self {
+        self.targets = Some(targets.into_iter().collect());
+        self
+    }
+
+    /// Restricts the filter to the given source agents.
+    #[inline]
+    pub fn sources(mut self, sources: impl IntoIterator<Item = u64>) -> Self {
+        self.sources = Some(sources.into_iter().collect());
+        self
+    }
+
+    /// Checks whether the given [`Hit`] matches the filter.
+    pub fn matches(&self, hit: &Hit) -> bool {
+        self.window
+            .map_or(true, |(start, end)| (start..=end).contains(&hit.time))
+            && self
+                .targets
+                .as_ref()
+                .map_or(true, |targets| targets.contains(&hit.dst))
+            && self
+                .sources
+                .as_ref()
+                .map_or(true, |sources| sources.contains(&hit.src))
+    }
+}
+
+/// Aggregation of hits from ArcDPS combat events.
+///
+/// Hits from minions are attributed to their master, if the master has been seen.
+#[derive(Debug, Clone, Default)]
+pub struct CombatStats {
+    hits: Vec<Hit>,
+    instances: BTreeMap<u16, u64>,
+    phases: Vec<Phase>,
+}
+
+impl CombatStats {
+    /// Creates a new empty aggregation.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            hits: Vec::new(),
+            instances: BTreeMap::new(),
+            phases: Vec::new(),
+        }
+    }
+
+    /// Processes the [`CombatData`] of a combat event.
+    #[inline]
+    pub fn process(&mut self, data: &CombatData) {
+        if let Some(event) = data.event() {
+            self.process_event(event);
+        }
+    }
+
+    /// Processes an [`Event`].
+    pub fn process_event(&mut self, event: &Event) {
+        if event.src_instance_id != 0 && event.src_agent != 0 {
+            self.instances
+                .insert(event.src_instance_id, event.src_agent);
+        }
+        if let Some(mut hit) = Hit::from_event(event) {
+            if event.src_master_instance_id != 0 {
+                if let Some(master) = self.instances.get(&event.src_master_instance_id) {
+                    hit.src = *master;
+                }
+            }
+            self.hits.push(hit);
+        }
+    }
+
+    /// Clears all recorded hits & phases.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.hits.clear();
+        self.instances.clear();
+        self.phases.clear();
+    }
+
+    /// Returns all recorded hits.
+    #[inline]
+    pub fn hits(&self) -> &[Hit] {
+        &self.hits
+    }
+
+    /// Returns the time of the first recorded hit.
+    #[inline]
+    pub fn start(&self) -> Option<u64> {
+        self.hits.iter().map(|hit| hit.time).min()
+    }
+
+    /// Returns the time of the last recorded hit.
+    #[inline]
+    pub fn end(&self) -> Option<u64> {
+        self.hits.iter().map(|hit| hit.time).max()
+    }
+
+    /// Adds a new [`Phase`].
+    #[inline]
+    pub fn add_phase(&mut self, phase: Phase) {
+        self.phases.push(phase);
+    }
+
+    /// Returns all added phases.
+    #[inline]
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// Aggregates the recorded hits matching the given [`StatsFilter`].
+    pub fn aggregate(&self, filter: &StatsFilter) -> Stats {
+        let mut stats = Stats::default();
+        for hit in self.hits.iter().filter(|hit| filter.matches(hit)) {
+            stats.add(hit);
+        }
+        if let Some((start, end)) = filter.window {
+            stats.start = start;
+            stats.end = end;
+        }
+        stats
+    }
+
+    /// Aggregates the recorded hits for each added [`Phase`].
+    pub fn aggregate_phases<'a>(
+        &'a self,
+        filter: &'a StatsFilter,
+    ) -> impl Iterator<Item = (&'a Phase, Stats)> + 'a {
+        self.phases.iter().map(move |phase| {
+            let filter = filter.clone().phase(phase);
+            (phase, self.aggregate(&filter))
+        })
+    }
+}
+
+/// Aggregated stats for a time window.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Start time in milliseconds.
+    pub start: u64,
+
+    /// End time in milliseconds.
+    pub end: u64,
+
+    /// Stats per source agent.
+    pub sources: BTreeMap<u64, SourceStats>,
+}
+
+impl Stats {
+    fn add(&mut self, hit: &Hit) {
+        if self.sources.is_empty() {
+            self.start = hit.time;
+            self.end = hit.time;
+        } else {
+            self.start = self.start.min(hit.time);
+            self.end = self.end.max(hit.time);
+        }
+        self.sources.entry(hit.src).or_default().add(hit);
+    }
+
+    /// Returns the duration of the window in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns the total damage of all sources.
+    #[inline]
+    pub fn total_damage(&self) -> u64 {
+        self.sources.values().map(SourceStats::damage).sum()
+    }
+
+    /// Returns the total healing of all sources.
+    #[inline]
+    pub fn total_healing(&self) -> u64 {
+        self.sources.values().map(|source| source.healing).sum()
+    }
+
+    /// Returns the combined damage per second of all sources.
+    #[inline]
+    pub fn total_dps(&self) -> f64 {
+        per_second(self.total_damage(), self.duration())
+    }
+}
+
+/// Aggregated stats of a source agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceStats {
+    /// Direct damage dealt.
+    pub power_damage: u64,
+
+    /// Condition damage dealt.
+    pub condition_damage: u64,
+
+    /// Healing done.
+    pub healing: u64,
+
+    /// Stats per skill id.
+    pub skills: BTreeMap<u32, SkillStats>,
+}
+
+impl SourceStats {
+    fn add(&mut self, hit: &Hit) {
+        match hit.kind {
+            HitKind::Power => self.power_damage += hit.amount,
+            HitKind::Condition => self.condition_damage += hit.amount,
+            HitKind::Healing => self.healing += hit.amount,
+        }
+        self.skills.entry(hit.skill_id).or_default().add(hit);
+    }
+
+    /// Returns the total damage dealt.
+    #[inline]
+    pub fn damage(&self) -> u64 {
+        self.power_damage + self.condition_damage
+    }
+
+    /// Returns the damage per second over the given duration in milliseconds.
+    #[inline]
+    pub fn dps(&self, duration: u64) -> f64 {
+        per_second(self.damage(), duration)
+    }
+
+    /// Returns the healing per second over the given duration in milliseconds.
+    #[inline]
+    pub fn hps(&self, duration: u64) -> f64 {
+        per_second(self.healing, duration)
+    }
+}
+
+/// Aggregated stats of a skill.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkillStats {
+    /// Damage dealt.
+    pub damage: u64,
+
+    /// Healing done.
+    pub healing: u64,
+
+    /// Number of hits.
+    pub hits: u32,
+
+    /// Number of critical hits.
+    pub crits: u32,
+}
+
+impl SkillStats {
+    fn add(&mut self, hit: &Hit) {
+        if hit.is_damage() {
+            self.damage += hit.amount;
+        } else {
+            self.healing += hit.amount;
+        }
+        self.hits += 1;
+        if hit.is_crit {
+            self.crits += 1;
+        }
+    }
+}
+
+fn per_second(amount: u64, duration: u64) -> f64 {
+    if duration > 0 {
+        amount as f64 / (duration as f64 / 1000.0)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strike(time: u64, src: u64, dst: u64, skill_id: u32, value: i32) -> Event {
+        Event {
+            time,
+            src_agent: src,
+            dst_agent: dst,
+            value,
+            skill_id,
+            ..Event::default()
+        }
+    }
+
+    fn condi(time: u64, src: u64, dst: u64, skill_id: u32, buff_dmg: i32) -> Event {
+        Event {
+            time,
+            src_agent: src,
+            dst_agent: dst,
+            buff_dmg,
+            skill_id,
+            buff: 1,
+            ..Event::default()
+        }
+    }
+
+    #[test]
+    fn aggregate_sources() {
+        let mut stats = CombatStats::new();
+        stats.process_event(&strike(1000, 1, 100, 5, 2000));
+        stats.process_event(&strike(2000, 1, 100, 6, 1000));
+        stats.process_event(&condi(3000, 2, 100, 737, 500));
+        stats.process_event(&strike(3000, 2, 100, 5, -300));
+
+        let result = stats.aggregate(&StatsFilter::new());
+        assert_eq!(result.duration(), 2000);
+        assert_eq!(result.total_damage(), 3500);
+        assert_eq!(result.total_healing(), 300);
+
+        let first = &result.sources[&1];
+        assert_eq!(first.power_damage, 3000);
+        assert_eq!(first.skills[&5].damage, 2000);
+        assert_eq!(first.dps(result.duration()), 1500.0);
+
+        let second = &result.sources[&2];
+        assert_eq!(second.condition_damage, 500);
+        assert_eq!(second.healing, 300);
+    }
+
+    #[test]
+    fn filter_targets_and_phases() {
+        let mut stats = CombatStats::new();
+        stats.process_event(&strike(1000, 1, 100, 5, 1000));
+        stats.process_event(&strike(2000, 1, 200, 5, 4000));
+        stats.process_event(&strike(5000, 1, 100, 5, 3000));
+        stats.add_phase(Phase::new("Phase 1", 0, 2500));
+
+        let targets = stats.aggregate(&StatsFilter::new().targets([100]));
+        assert_eq!(targets.total_damage(), 4000);
+
+        let (phase, phase_stats) = stats.aggregate_phases(&StatsFilter::new()).next().unwrap();
+        assert_eq!(phase.name, "Phase 1");
+        assert_eq!(phase_stats.duration(), 2500);
+        assert_eq!(phase_stats.total_damage(), 5000);
+    }
+
+    #[test]
+    fn ignore_non_damage() {
+        let apply = Event {
+            buff: 1,
+            value: 5000,
+            ..Event::default()
+        };
+        let statechange = Event {
+            is_statechange: 1,
+            value: 100,
+            ..Event::default()
+        };
+        assert_eq!(Hit::from_event(&apply), None);
+        assert_eq!(Hit::from_event(&statechange), None);
+    }
+
+    #[test]
+    fn minion_to_master() {
+        let mut stats = CombatStats::new();
+        stats.process_event(&Event {
+            src_instance_id: 10,
+            ..strike(1000, 1, 100, 5, 100)
+        });
+        stats.process_event(&Event {
+            src_instance_id: 11,
+            src_master_instance_id: 10,
+            ..strike(2000, 2, 100, 6, 200)
+        });
+
+        let result = stats.aggregate(&StatsFilter::new());
+        assert_eq!(result.sources.len(), 1);
+        assert_eq!(result.sources[&1].damage(), 300);
+    }
+}
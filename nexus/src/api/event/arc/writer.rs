@@ -0,0 +1,702 @@
+//! Writer for ArcDPS compatible `.evtc` logs from the combat stream.
+//!
+//! Logs consist of a header, an agent table, a skill table and the event stream (revision 1).
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{
+//!     event::{arc::{writer::{EvtcRecorder, Rotation}, CombatData, COMBAT_SQUAD}, event_consume},
+//!     paths::get_addon_dir,
+//! };
+//! use std::sync::{Mutex, OnceLock};
+//!
+//! static RECORDER: OnceLock<Mutex<EvtcRecorder>> = OnceLock::new();
+//!
+//! let dir = get_addon_dir("my_addon").expect("invalid addon dir").join("logs");
+//! let _ = RECORDER.set(Mutex::new(EvtcRecorder::new(dir, Rotation::KeepLast(20))));
+//!
+//! COMBAT_SQUAD
+//!     .subscribe(event_consume!(<CombatData> |data| {
+//!         if let (Some(data), Some(recorder)) = (data, RECORDER.get()) {
+//!             if let Ok(Some(path)) = recorder.lock().unwrap().process(data) {
+//!                 // log written to path
+//!             }
+//!         }
+//!     }))
+//!     .revert_on_unload();
+//! ```
+
+use super::{
+    encounter::{EncounterEvent, EncounterTracker},
+    AgentUpdate, CombatData,
+};
+use crate::util::str_from_c;
+use arcdps::evtc::{Agent, Event};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, ErrorKind, Write},
+    mem,
+    path::{Path, PathBuf},
+    slice,
+    time::SystemTime,
+};
+
+/// EVTC log revision written.
+pub const REVISION: u8 = 1;
+
+/// Size of the log header in bytes.
+pub const HEADER_SIZE: usize = 16;
+
+/// Size of an agent table entry in bytes.
+pub const AGENT_SIZE: usize = 96;
+
+/// Size of a skill table entry in bytes.
+pub const SKILL_SIZE: usize = 68;
+
+/// Size of an event in bytes.
+pub const EVENT_SIZE: usize = 64;
+
+/// Size of names in agent & skill tables.
+const NAME_SIZE: usize = 64;
+
+const _: () = assert!(mem::size_of::<Event>() == EVENT_SIZE);
+
+/// Agent table entry of an EVTC log.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvtcAgent {
+    /// ArcDPS id of the agent.
+    pub id: u64,
+
+    /// Profession or species id.
+    pub prof: u32,
+
+    /// Elite specialization or `0xFFFFFFFF` for non-player characters.
+    pub elite: u32,
+
+    /// Toughness rating.
+    pub toughness: i16,
+
+    /// Concentration rating.
+    pub concentration: i16,
+
+    /// Healing rating.
+    pub healing: i16,
+
+    /// Hitbox width.
+    pub hitbox_width: i16,
+
+    /// Condition damage rating.
+    pub condition: i16,
+
+    /// Hitbox height.
+    pub hitbox_height: i16,
+
+    /// Character or NPC name.
+    pub name: String,
+
+    /// Account name for player characters.
+    pub account: Option<String>,
+
+    /// Subgroup for player characters.
+    pub subgroup: Option<u16>,
+}
+
+impl EvtcAgent {
+    /// Creates an agent table entry from an [`Agent`] of the combat stream.
+    pub fn from_agent(agent: &Agent) -> Self {
+        Self {
+            id: agent.id as u64,
+            prof: agent.prof,
+            elite: agent.elite,
+            name: unsafe { str_from_c(agent.name) }
+                .unwrap_or_default()
+                .to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Creates an agent table entry from an [`AgentUpdate`].
+    pub fn from_agent_update(agent: &AgentUpdate) -> Self {
+        Self {
+            id: agent.id as u64,
+            prof: agent.prof,
+            elite: agent.elite,
+            name: agent.character().to_string_lossy().into_owned(),
+            account: Some(agent.account().to_string_lossy().into_owned()),
+            subgroup: Some(agent.subgroup),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the packed name as written to the agent table.
+    ///
+    /// Player names are packed as `character\0account\0subgroup\0`.
+    pub fn packed_name(&self) -> [u8; NAME_SIZE] {
+        let mut packed = Vec::with_capacity(NAME_SIZE);
+        packed.extend_from_slice(self.name.as_bytes());
+        packed.push(0);
+        if let Some(account) = &self.account {
+            packed.extend_from_slice(account.as_bytes());
+            packed.push(0);
+            if let Some(subgroup) = self.subgroup {
+                packed.extend_from_slice(subgroup.to_string().as_bytes());
+                packed.push(0);
+            }
+        }
+        fixed_name(&packed)
+    }
+
+    /// Writes the agent table entry.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&self.id.to_le_bytes())?;
+        writer.write_all(&self.prof.to_le_bytes())?;
+        writer.write_all(&self.elite.to_le_bytes())?;
+        for stat in [
+            self.toughness,
+            self.concentration,
+            self.healing,
+            self.hitbox_width,
+            self.condition,
+            self.hitbox_height,
+        ] {
+            writer.write_all(&stat.to_le_bytes())?;
+        }
+        writer.write_all(&self.packed_name())?;
+        writer.write_all(&[0; 4])
+    }
+}
+
+/// In-memory EVTC log.
+#[derive(Debug, Clone)]
+pub struct EvtcLog {
+    /// ArcDPS build date as `YYYYMMDD`.
+    pub build: [u8; 8],
+
+    /// Species id of the boss.
+    pub species_id: u16,
+
+    /// Agent table.
+    pub agents: BTreeMap<u64, EvtcAgent>,
+
+    /// Skill table.
+    pub skills: BTreeMap<u32, String>,
+
+    /// Event stream.
+    pub events: Vec<Event>,
+}
+
+impl EvtcLog {
+    /// Default ArcDPS build date written to the header.
+    ///
+    /// The ArcDPS build is not exposed to Nexus addons, so this is a placeholder.
+    /// Parsers only use the build to decide on format quirks of older builds.
+    /// Use [`EvtcRecorder::with_build`] if the actual build is known.
+    pub const BUILD: [u8; 8] = *b"20250101";
+
+    /// Creates a new empty log for the given boss species.
+    #[inline]
+    pub fn new(species_id: u16) -> Self {
+        Self {
+            build: Self::BUILD,
+            species_id,
+            agents: BTreeMap::new(),
+            skills: BTreeMap::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Adds or updates an agent.
+    ///
+    /// Account & subgroup information already known is kept.
+    pub fn add_agent(&mut self, agent: EvtcAgent) {
+        match self.agents.get_mut(&agent.id) {
+            Some(existing) => {
+                existing.prof = agent.prof;
+                existing.elite = agent.elite;
+                if !agent.name.is_empty() {
+                    existing.name = agent.name;
+                }
+                if agent.account.is_some() {
+                    existing.account = agent.account;
+                    existing.subgroup = agent.subgroup;
+                }
+            }
+            None => {
+                self.agents.insert(agent.id, agent);
+            }
+        }
+    }
+
+    /// Sets the name of a skill.
+    #[inline]
+    pub fn add_skill(&mut self, id: u32, name: impl Into<String>) {
+        self.skills.insert(id, name.into());
+    }
+
+    /// Adds an event to the stream.
+    ///
+    /// Skills used by the event are added to the skill table without name.
+    #[inline]
+    pub fn add_event(&mut self, event: Event) {
+        if event.skill_id != 0 {
+            self.skills.entry(event.skill_id).or_default();
+        }
+        self.events.push(event);
+    }
+
+    /// Adds the agents & event of the [`CombatData`].
+    pub fn add_combat(&mut self, data: &CombatData) {
+        for agent in [data.src(), data.dst()].into_iter().flatten() {
+            self.add_agent(EvtcAgent::from_agent(agent));
+        }
+        if let Some(event) = data.event() {
+            if let Some(name) = data.skill_name().filter(|name| !name.is_empty()) {
+                self.add_skill(event.skill_id, name);
+            }
+            self.add_event(event.clone());
+        }
+    }
+
+    /// Returns the size of the encoded log in bytes.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        HEADER_SIZE
+            + 4
+            + self.agents.len() * AGENT_SIZE
+            + 4
+            + self.skills.len() * SKILL_SIZE
+            + self.events.len() * EVENT_SIZE
+    }
+
+    /// Writes the log in EVTC format.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        // header
+        writer.write_all(b"EVTC")?;
+        writer.write_all(&self.build)?;
+        writer.write_all(&[REVISION])?;
+        writer.write_all(&self.species_id.to_le_bytes())?;
+        writer.write_all(&[0])?;
+
+        // agents
+        writer.write_all(&(self.agents.len() as u32).to_le_bytes())?;
+        for agent in self.agents.values() {
+            agent.write(&mut writer)?;
+        }
+
+        // skills
+        writer.write_all(&(self.skills.len() as u32).to_le_bytes())?;
+        for (id, name) in &self.skills {
+            writer.write_all(&(*id as i32).to_le_bytes())?;
+            writer.write_all(&fixed_name(name.as_bytes()))?;
+        }
+
+        // events
+        for event in &self.events {
+            // event has the same C layout as written by arcdps
+            let bytes =
+                unsafe { slice::from_raw_parts((event as *const Event).cast::<u8>(), EVENT_SIZE) };
+            writer.write_all(bytes)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Writes the log to a file at the given path.
+    #[inline]
+    pub fn write_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = File::create(path)?;
+        self.write(BufWriter::new(file))
+    }
+}
+
+/// Rotation policy for [`EvtcRecorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// Write a new file for every encounter.
+    PerEncounter,
+
+    /// Write a new file for every encounter and keep only the last files per species.
+    ///
+    /// Only files written by the recorder are removed.
+    /// A count of `0` keeps all files.
+    KeepLast(usize),
+}
+
+/// Records encounters from the combat stream into EVTC files.
+///
+/// Files are written to `<dir>/<species id>/<unix timestamp in ms>.evtc` when an encounter ends.
+/// Logs written within the same millisecond receive a `-<n>` suffix.
+#[derive(Debug, Clone)]
+pub struct EvtcRecorder {
+    dir: PathBuf,
+    rotation: Rotation,
+    build: [u8; 8],
+    tracker: EncounterTracker,
+    agents: BTreeMap<u64, EvtcAgent>,
+    skills: BTreeMap<u32, String>,
+    log: Option<EvtcLog>,
+}
+
+impl EvtcRecorder {
+    /// Creates a new recorder writing to the given directory.
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>, rotation: Rotation) -> Self {
+        Self {
+            dir: dir.into(),
+            rotation,
+            build: EvtcLog::BUILD,
+            tracker: EncounterTracker::new(),
+            agents: BTreeMap::new(),
+            skills: BTreeMap::new(),
+            log: None,
+        }
+    }
+
+    /// Sets the ArcDPS build date as `YYYYMMDD` written to log headers.
+    #[inline]
+    pub fn with_build(mut self, build: [u8; 8]) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// Returns the [`EncounterTracker`] deciding when logs start & end.
+    #[inline]
+    pub fn tracker_mut(&mut self) -> &mut EncounterTracker {
+        &mut self.tracker
+    }
+
+    /// Checks whether a log is being recorded.
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.log.is_some()
+    }
+
+    /// Sets the name of a skill for written logs.
+    #[inline]
+    pub fn add_skill(&mut self, id: u32, name: impl Into<String>) {
+        let name = name.into();
+        if let Some(log) = &mut self.log {
+            log.add_skill(id, name.clone());
+        }
+        self.skills.insert(id, name);
+    }
+
+    /// Processes an [`AgentUpdate`] from [`SELF_JOIN`](super::SELF_JOIN), [`SQUAD_JOIN`](super::SQUAD_JOIN) or [`SQUAD_LEAVE`](super::SQUAD_LEAVE).
+    ///
+    /// Agents leaving are kept in the log currently being recorded, but not added to later logs.
+    pub fn process_agent_update(&mut self, agent: &AgentUpdate) {
+        self.tracker.process_agent_update(agent);
+        if !agent.is_added() {
+            self.agents.remove(&(agent.id as u64));
+            return;
+        }
+        let agent = EvtcAgent::from_agent_update(agent);
+        if let Some(log) = &mut self.log {
+            log.add_agent(agent.clone());
+        }
+        self.agents.insert(agent.id, agent);
+    }
+
+    /// Processes the [`CombatData`] of a combat event.
+    ///
+    /// Returns the path of the written file when an encounter ended.
+    pub fn process(&mut self, data: &CombatData) -> io::Result<Option<PathBuf>> {
+        if let (Some(event), Some(name)) = (data.event(), data.skill_name()) {
+            if !name.is_empty() {
+                self.skills.insert(event.skill_id, name.to_owned());
+            }
+        }
+        match self.tracker.process(data) {
+            Some(EncounterEvent::Started(started)) => {
+                let species_id = u16::try_from(started.species_id).map_err(|_| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("species id {} exceeds EVTC header", started.species_id),
+                    )
+                })?;
+                let mut log = EvtcLog::new(species_id);
+                log.build = self.build;
+                log.agents = self.agents.clone();
+                log.skills = self.skills.clone();
+                log.add_combat(data);
+                self.log = Some(log);
+                Ok(None)
+            }
+            Some(EncounterEvent::Ended(_)) => {
+                let Some(mut log) = self.log.take() else {
+                    return Ok(None);
+                };
+                log.add_combat(data);
+                self.write(&log).map(Some)
+            }
+            None => {
+                if let Some(log) = &mut self.log {
+                    log.add_combat(data);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Writes the log currently being recorded and stops recording.
+    pub fn flush(&mut self) -> io::Result<Option<PathBuf>> {
+        self.tracker.reset();
+        match self.log.take() {
+            Some(log) => self.write(&log).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn write(&self, log: &EvtcLog) -> io::Result<PathBuf> {
+        let dir = self.dir.join(log.species_id.to_string());
+        fs::create_dir_all(&dir)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let (path, file) = create_unique(&dir, timestamp)?;
+        log.write(BufWriter::new(file))?;
+
+        match self.rotation {
+            Rotation::KeepLast(count) if count > 0 => {
+                let mut files = fs::read_dir(&dir)?
+                    .filter_map(Result::ok)
+                    .filter_map(|entry| {
+                        let path = entry.path();
+                        let key = parse_log_name(path.file_name()?.to_str()?)?;
+                        Some((key, path))
+                    })
+                    .collect::<Vec<_>>();
+                files.sort();
+                let excess = files.len().saturating_sub(count);
+                for (_, file) in &files[..excess] {
+                    fs::remove_file(file)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(path)
+    }
+}
+
+/// Creates a new log file named after the timestamp, adding a suffix if it already exists.
+fn create_unique(dir: &Path, timestamp: u128) -> io::Result<(PathBuf, File)> {
+    for index in 0u32.. {
+        let name = match index {
+            0 => format!("{timestamp}.evtc"),
+            _ => format!("{timestamp}-{index}.evtc"),
+        };
+        let path = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(
+        ErrorKind::AlreadyExists,
+        "no free log file name",
+    ))
+}
+
+/// Parses a log file name written by [`EvtcRecorder`] into timestamp & suffix.
+fn parse_log_name(name: &str) -> Option<(u128, u32)> {
+    let stem = name.strip_suffix(".evtc")?;
+    let (timestamp, index) = match stem.split_once('-') {
+        Some((timestamp, index)) => (timestamp, index.parse().ok()?),
+        None => (stem, 0),
+    };
+    if timestamp.is_empty() || !timestamp.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    Some((timestamp.parse().ok()?, index))
+}
+
+/// Copies bytes into a nul-terminated fixed size name.
+fn fixed_name(bytes: &[u8]) -> [u8; NAME_SIZE] {
+    let mut name = [0; NAME_SIZE];
+    let len = bytes.len().min(NAME_SIZE - 1);
+    name[..len].copy_from_slice(&bytes[..len]);
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arcdps::evtc::StateChange;
+    use std::ptr;
+
+    #[test]
+    fn header_layout() {
+        let mut buffer = Vec::new();
+        EvtcLog::new(15438).write(&mut buffer).unwrap();
+
+        assert_eq!(buffer.len(), HEADER_SIZE + 8);
+        assert_eq!(&buffer[0..4], b"EVTC");
+        assert_eq!(&buffer[4..12], &EvtcLog::BUILD);
+        assert_eq!(buffer[12], REVISION);
+        assert_eq!(&buffer[13..15], &15438u16.to_le_bytes());
+        assert_eq!(buffer[15], 0);
+        assert_eq!(&buffer[16..20], &0u32.to_le_bytes());
+        assert_eq!(&buffer[20..24], &0u32.to_le_bytes());
+    }
+
+    #[test]
+    fn agent_layout() {
+        let agent = EvtcAgent {
+            id: 0x1234,
+            prof: 7,
+            elite: 64,
+            toughness: 10,
+            hitbox_width: 46,
+            hitbox_height: 96,
+            name: "Character".into(),
+            account: Some(":Account.1234".into()),
+            subgroup: Some(3),
+            ..EvtcAgent::default()
+        };
+        let mut buffer = Vec::new();
+        agent.write(&mut buffer).unwrap();
+
+        assert_eq!(buffer.len(), AGENT_SIZE);
+        assert_eq!(&buffer[0..8], &0x1234u64.to_le_bytes());
+        assert_eq!(&buffer[8..12], &7u32.to_le_bytes());
+        assert_eq!(&buffer[12..16], &64u32.to_le_bytes());
+        assert_eq!(&buffer[16..18], &10i16.to_le_bytes());
+        assert_eq!(&buffer[22..24], &46i16.to_le_bytes());
+        assert_eq!(&buffer[26..28], &96i16.to_le_bytes());
+        assert_eq!(&buffer[28..52], b"Character\0:Account.1234\03\0");
+        assert!(buffer[52..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn log_layout() {
+        let mut log = EvtcLog::new(1);
+        log.add_agent(EvtcAgent {
+            id: 1,
+            name: "Boss".into(),
+            elite: u32::MAX,
+            ..EvtcAgent::default()
+        });
+        log.add_skill(5, "Skill");
+        log.add_event(Event {
+            time: 0xAABB,
+            src_agent: 1,
+            skill_id: 5,
+            value: -1,
+            ..Event::default()
+        });
+
+        let mut buffer = Vec::new();
+        log.write(&mut buffer).unwrap();
+        assert_eq!(buffer.len(), log.encoded_len());
+
+        let skills = HEADER_SIZE + 4 + AGENT_SIZE;
+        assert_eq!(&buffer[skills..skills + 4], &1u32.to_le_bytes());
+        assert_eq!(&buffer[skills + 4..skills + 8], &5i32.to_le_bytes());
+        assert_eq!(&buffer[skills + 8..skills + 14], b"Skill\0");
+
+        let event = skills + 4 + SKILL_SIZE;
+        assert_eq!(&buffer[event..event + 8], &0xAABBu64.to_le_bytes());
+        assert_eq!(&buffer[event + 8..event + 16], &1u64.to_le_bytes());
+        assert_eq!(&buffer[event + 24..event + 28], &(-1i32).to_le_bytes());
+        assert_eq!(&buffer[event + 36..event + 40], &5u32.to_le_bytes());
+    }
+
+    #[test]
+    fn unique_names_and_rotation() {
+        let dir = std::env::temp_dir().join(format!("nexus_evtc_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let species = dir.join("1");
+        fs::create_dir_all(&species).unwrap();
+        fs::write(species.join("foreign.evtc"), b"").unwrap();
+        fs::write(species.join("1.evtc"), b"").unwrap();
+
+        let (first, _) = create_unique(&species, 5).unwrap();
+        let (second, _) = create_unique(&species, 5).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(parse_log_name("5-1.evtc"), Some((5, 1)));
+        assert_eq!(parse_log_name("foreign.evtc"), None);
+
+        let recorder = EvtcRecorder::new(&dir, Rotation::KeepLast(2));
+        let written = recorder.write(&EvtcLog::new(1)).unwrap();
+        let mut remaining = fs::read_dir(&species)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        let written = written.file_name().unwrap().to_str().unwrap().to_owned();
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.contains(&"foreign.evtc".to_owned()));
+        assert!(remaining.contains(&"5-1.evtc".to_owned()));
+        assert!(remaining.contains(&written));
+
+        let keep_all = EvtcRecorder::new(&dir, Rotation::KeepLast(0));
+        keep_all.write(&EvtcLog::new(1)).unwrap();
+        assert_eq!(fs::read_dir(&species).unwrap().count(), 4);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn left_agents_not_recorded() {
+        fn agent_update(id: usize, added: bool) -> AgentUpdate {
+            AgentUpdate {
+                account: [0; 64],
+                character: [0; 64],
+                id,
+                instance_id: id,
+                added: added.into(),
+                target: 0,
+                is_self: 0,
+                prof: 1,
+                elite: 0,
+                team: 0,
+                subgroup: 1,
+            }
+        }
+
+        fn log_start(species_id: u32) -> Event {
+            Event {
+                src_agent: species_id as u64,
+                is_statechange: StateChange::LogStart.into(),
+                ..Event::default()
+            }
+        }
+
+        let mut recorder = EvtcRecorder::new(std::env::temp_dir(), Rotation::KeepLast(0));
+        recorder.process_agent_update(&agent_update(1, true));
+        recorder.process_agent_update(&agent_update(2, true));
+        recorder.process_agent_update(&agent_update(2, false));
+
+        let event = log_start(15438);
+        let data = CombatData {
+            event: &event,
+            src: ptr::null(),
+            dst: ptr::null(),
+            skill_name: ptr::null(),
+            id: 1,
+            rev: 1,
+        };
+        assert_eq!(recorder.process(&data).unwrap(), None);
+        let log = recorder.log.as_ref().expect("encounter not started");
+        assert_eq!(log.agents.keys().copied().collect::<Vec<_>>(), [1]);
+
+        // agents leaving mid encounter stay in the current log
+        recorder.process_agent_update(&agent_update(1, false));
+        assert!(recorder.log.as_ref().unwrap().agents.contains_key(&1));
+        assert!(recorder.agents.is_empty());
+    }
+
+    #[test]
+    fn truncate_names() {
+        let name = fixed_name(&[b'a'; 100]);
+        assert_eq!(name[NAME_SIZE - 2], b'a');
+        assert_eq!(name[NAME_SIZE - 1], 0);
+    }
+}
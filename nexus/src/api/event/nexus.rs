@@ -1,6 +1,11 @@
 //! Nexus events.
 
-use super::Event;
+use super::{event_consume, Event};
+use crate::{
+    game::{impl_game_ids, Profession, Race, Specialization},
+    revertible::Revertible,
+};
+use std::sync::Mutex;
 
 /// Nexus addon loaded event.
 pub const ADDON_LOADED: Event<i32> = unsafe { Event::new("EV_ADDON_LOADED") };
@@ -19,6 +24,9 @@ pub const MUMBLE_IDENTITY_UPDATED: Event<MumbleIdentityUpdate> =
     unsafe { Event::new("EV_MUMBLE_IDENTITY_UPDATED") };
 
 /// Mumble identity.
+///
+/// This struct uses the C layout.
+/// Instead of cloning this it is recommended to convert to [`MumbleIdentity`] via [`Into`] or [`to_owned`](MumbleIdentityUpdate::to_owned).
 #[derive(Debug, Clone)]
 #[repr(C)]
 pub struct MumbleIdentityUpdate {
@@ -33,3 +41,123 @@ pub struct MumbleIdentityUpdate {
     pub fov: f32,
     pub ui_size: u32,
 }
+
+impl_game_ids!(MumbleIdentityUpdate {
+    profession,
+    specialization
+});
+
+impl MumbleIdentityUpdate {
+    /// Converts the identity to a [`MumbleIdentity`].
+    #[inline]
+    pub fn to_owned(&self) -> MumbleIdentity {
+        self.into()
+    }
+
+    /// Returns the character name bytes up to the first nul byte.
+    #[inline]
+    pub fn name_bytes(&self) -> &[u8] {
+        let len = self
+            .name
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(self.name.len());
+        &self.name[..len]
+    }
+
+    /// Returns the character name as owned [`String`].
+    #[inline]
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(self.name_bytes()).into_owned()
+    }
+
+    /// Returns the character [`Profession`].
+    #[inline]
+    pub fn profession(&self) -> Result<Profession, u32> {
+        self.profession.try_into()
+    }
+
+    /// Returns the character third [`Specialization`].
+    #[inline]
+    pub fn specialization(&self) -> Result<Specialization, u32> {
+        self.specialization.try_into()
+    }
+
+    /// Returns the character [`Race`].
+    #[inline]
+    pub fn race(&self) -> Result<Race, u32> {
+        self.race.try_into()
+    }
+}
+
+/// Mumble identity as owned version.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MumbleIdentity {
+    /// Character name.
+    pub name: String,
+
+    /// Character profession.
+    pub profession: Result<Profession, u32>,
+
+    /// Character third specialization.
+    pub specialization: Result<Specialization, u32>,
+
+    /// Character race.
+    pub race: Result<Race, u32>,
+
+    /// Map id of current map.
+    pub map_id: u32,
+
+    /// World id of current server.
+    pub world_id: u32,
+
+    /// Team color id.
+    pub team_color_id: u32,
+
+    /// Whether the character has a commander tag active.
+    pub is_commander: bool,
+
+    /// Field of view.
+    pub fov: f32,
+
+    /// Interface size.
+    pub ui_size: u32,
+}
+
+impl From<&MumbleIdentityUpdate> for MumbleIdentity {
+    fn from(identity: &MumbleIdentityUpdate) -> Self {
+        Self {
+            name: identity.name(),
+            profession: identity.profession(),
+            specialization: identity.specialization(),
+            race: identity.race(),
+            map_id: identity.map_id,
+            world_id: identity.world_id,
+            team_color_id: identity.team_color_id,
+            is_commander: identity.is_commander,
+            fov: identity.fov,
+            ui_size: identity.ui_size,
+        }
+    }
+}
+
+/// Current Mumble identity.
+static MUMBLE_IDENTITY: Mutex<Option<MumbleIdentity>> = Mutex::new(None);
+
+/// Subscribes to [`MUMBLE_IDENTITY_UPDATED`] to track the current [`MumbleIdentity`].
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+pub fn subscribe_mumble_identity() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    MUMBLE_IDENTITY_UPDATED.subscribe(event_consume!(<MumbleIdentityUpdate> |identity| {
+        *MUMBLE_IDENTITY.lock().unwrap() = identity.map(Into::into);
+    }))
+}
+
+/// Returns the current [`MumbleIdentity`].
+///
+/// Requires [`subscribe_mumble_identity`] to be called first.
+#[inline]
+pub fn current_mumble_identity() -> Option<MumbleIdentity> {
+    MUMBLE_IDENTITY.lock().unwrap().clone()
+}
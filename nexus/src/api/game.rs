@@ -0,0 +1,568 @@
+//! Guild Wars 2 game data.
+
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// Character profession.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum Profession {
+    Guardian = 1,
+    Warrior = 2,
+    Engineer = 3,
+    Ranger = 4,
+    Thief = 5,
+    Elementalist = 6,
+    Mesmer = 7,
+    Necromancer = 8,
+    Revenant = 9,
+}
+
+impl Profession {
+    /// Returns the UI color of the profession as RGB bytes.
+    pub fn color_rgb(&self) -> [u8; 3] {
+        match self {
+            Self::Guardian => [0x72, 0xC1, 0xD9],
+            Self::Warrior => [0xFF, 0xD1, 0x66],
+            Self::Engineer => [0xD0, 0x9C, 0x59],
+            Self::Ranger => [0x8C, 0xDC, 0x82],
+            Self::Thief => [0xC0, 0x8F, 0x95],
+            Self::Elementalist => [0xF6, 0x8A, 0x87],
+            Self::Mesmer => [0xB6, 0x79, 0xD5],
+            Self::Necromancer => [0x52, 0xA7, 0x6F],
+            Self::Revenant => [0xD1, 0x6E, 0x5A],
+        }
+    }
+
+    /// Returns the UI color of the profession as RGBA floats, as used by imgui.
+    #[inline]
+    pub fn color(&self) -> [f32; 4] {
+        let [r, g, b] = self.color_rgb();
+        [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0]
+    }
+}
+
+/// Character race.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum Race {
+    Asura = 0,
+    Charr = 1,
+    Human = 2,
+    Norn = 3,
+    Sylvari = 4,
+}
+
+/// Character specialization.
+///
+/// Includes core and elite specializations.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum Specialization {
+    Dueling = 1,
+    DeathMagic = 2,
+    Invocation = 3,
+    Strength = 4,
+    Druid = 5,
+    Explosives = 6,
+    Daredevil = 7,
+    Marksmanship = 8,
+    Retribution = 9,
+    Domination = 10,
+    Tactics = 11,
+    Salvation = 12,
+    Valor = 13,
+    Corruption = 14,
+    Devastation = 15,
+    Radiance = 16,
+    Water = 17,
+    Berserker = 18,
+    BloodMagic = 19,
+    ShadowArts = 20,
+    Tools = 21,
+    Defense = 22,
+    Inspiration = 23,
+    Illusions = 24,
+    NatureMagic = 25,
+    Earth = 26,
+    Dragonhunter = 27,
+    DeadlyArts = 28,
+    Alchemy = 29,
+    Skirmishing = 30,
+    Fire = 31,
+    Beastmastery = 32,
+    WildernessSurvival = 33,
+    Reaper = 34,
+    CriticalStrikes = 35,
+    Arms = 36,
+    Arcane = 37,
+    Firearms = 38,
+    Curses = 39,
+    Chronomancer = 40,
+    Air = 41,
+    Zeal = 42,
+    Scrapper = 43,
+    Trickery = 44,
+    Chaos = 45,
+    Virtues = 46,
+    Inventions = 47,
+    Tempest = 48,
+    Honor = 49,
+    SoulReaping = 50,
+    Discipline = 51,
+    Herald = 52,
+    Spite = 53,
+    Acrobatics = 54,
+    Soulbeast = 55,
+    Weaver = 56,
+    Holosmith = 57,
+    Deadeye = 58,
+    Mirage = 59,
+    Scourge = 60,
+    Spellbreaker = 61,
+    Firebrand = 62,
+    Renegade = 63,
+    Harbinger = 64,
+    Willbender = 65,
+    Virtuoso = 66,
+    Catalyst = 67,
+    Bladesworn = 68,
+    Vindicator = 69,
+    Mechanist = 70,
+    Specter = 71,
+    Untamed = 72,
+    Troubadour = 73,
+    Paragon = 74,
+    Amalgam = 75,
+    Ritualist = 76,
+    Antiquary = 77,
+    Galeshot = 78,
+    Conduit = 79,
+    Evoker = 80,
+    Luminary = 81,
+}
+
+impl Specialization {
+    /// Returns the [`Profession`] of the specialization.
+    pub fn profession(&self) -> Profession {
+        match self {
+            Self::Dueling
+            | Self::Domination
+            | Self::Inspiration
+            | Self::Illusions
+            | Self::Chronomancer
+            | Self::Chaos
+            | Self::Mirage
+            | Self::Virtuoso
+            | Self::Troubadour => Profession::Mesmer,
+            Self::DeathMagic
+            | Self::BloodMagic
+            | Self::Reaper
+            | Self::Curses
+            | Self::SoulReaping
+            | Self::Spite
+            | Self::Scourge
+            | Self::Harbinger
+            | Self::Ritualist => Profession::Necromancer,
+            Self::Invocation
+            | Self::Retribution
+            | Self::Salvation
+            | Self::Corruption
+            | Self::Devastation
+            | Self::Herald
+            | Self::Renegade
+            | Self::Vindicator
+            | Self::Conduit => Profession::Revenant,
+            Self::Strength
+            | Self::Tactics
+            | Self::Berserker
+            | Self::Defense
+            | Self::Arms
+            | Self::Discipline
+            | Self::Spellbreaker
+            | Self::Bladesworn
+            | Self::Paragon => Profession::Warrior,
+            Self::Druid
+            | Self::Marksmanship
+            | Self::NatureMagic
+            | Self::Skirmishing
+            | Self::Beastmastery
+            | Self::WildernessSurvival
+            | Self::Soulbeast
+            | Self::Untamed
+            | Self::Galeshot => Profession::Ranger,
+            Self::Explosives
+            | Self::Tools
+            | Self::Alchemy
+            | Self::Firearms
+            | Self::Scrapper
+            | Self::Inventions
+            | Self::Holosmith
+            | Self::Mechanist
+            | Self::Amalgam => Profession::Engineer,
+            Self::Daredevil
+            | Self::ShadowArts
+            | Self::DeadlyArts
+            | Self::CriticalStrikes
+            | Self::Trickery
+            | Self::Acrobatics
+            | Self::Deadeye
+            | Self::Specter
+            | Self::Antiquary => Profession::Thief,
+            Self::Valor
+            | Self::Radiance
+            | Self::Dragonhunter
+            | Self::Zeal
+            | Self::Virtues
+            | Self::Honor
+            | Self::Firebrand
+            | Self::Willbender
+            | Self::Luminary => Profession::Guardian,
+            Self::Water
+            | Self::Earth
+            | Self::Fire
+            | Self::Arcane
+            | Self::Air
+            | Self::Tempest
+            | Self::Weaver
+            | Self::Catalyst
+            | Self::Evoker => Profession::Elementalist,
+        }
+    }
+
+    /// Checks whether the specialization is an elite specialization.
+    pub fn is_elite(&self) -> bool {
+        matches!(
+            self,
+            Self::Druid
+                | Self::Daredevil
+                | Self::Berserker
+                | Self::Dragonhunter
+                | Self::Reaper
+                | Self::Chronomancer
+                | Self::Scrapper
+                | Self::Tempest
+                | Self::Herald
+                | Self::Soulbeast
+                | Self::Weaver
+                | Self::Holosmith
+                | Self::Deadeye
+                | Self::Mirage
+                | Self::Scourge
+                | Self::Spellbreaker
+                | Self::Firebrand
+                | Self::Renegade
+                | Self::Harbinger
+                | Self::Willbender
+                | Self::Virtuoso
+                | Self::Catalyst
+                | Self::Bladesworn
+                | Self::Vindicator
+                | Self::Mechanist
+                | Self::Specter
+                | Self::Untamed
+                | Self::Troubadour
+                | Self::Paragon
+                | Self::Amalgam
+                | Self::Ritualist
+                | Self::Antiquary
+                | Self::Galeshot
+                | Self::Conduit
+                | Self::Evoker
+                | Self::Luminary
+        )
+    }
+}
+
+impl From<Specialization> for Profession {
+    #[inline]
+    fn from(spec: Specialization) -> Self {
+        spec.profession()
+    }
+}
+
+/// Elite specialization.
+///
+/// Values match the respective [`Specialization`].
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum EliteSpecialization {
+    Druid = 5,
+    Daredevil = 7,
+    Berserker = 18,
+    Dragonhunter = 27,
+    Reaper = 34,
+    Chronomancer = 40,
+    Scrapper = 43,
+    Tempest = 48,
+    Herald = 52,
+    Soulbeast = 55,
+    Weaver = 56,
+    Holosmith = 57,
+    Deadeye = 58,
+    Mirage = 59,
+    Scourge = 60,
+    Spellbreaker = 61,
+    Firebrand = 62,
+    Renegade = 63,
+    Harbinger = 64,
+    Willbender = 65,
+    Virtuoso = 66,
+    Catalyst = 67,
+    Bladesworn = 68,
+    Vindicator = 69,
+    Mechanist = 70,
+    Specter = 71,
+    Untamed = 72,
+    Troubadour = 73,
+    Paragon = 74,
+    Amalgam = 75,
+    Ritualist = 76,
+    Antiquary = 77,
+    Galeshot = 78,
+    Conduit = 79,
+    Evoker = 80,
+    Luminary = 81,
+}
+
+impl EliteSpecialization {
+    /// Returns the base [`Profession`] of the elite specialization.
+    #[inline]
+    pub fn profession(&self) -> Profession {
+        Specialization::from(*self).profession()
+    }
+}
+
+impl From<EliteSpecialization> for Specialization {
+    #[inline]
+    fn from(elite: EliteSpecialization) -> Self {
+        match elite {
+            EliteSpecialization::Druid => Self::Druid,
+            EliteSpecialization::Daredevil => Self::Daredevil,
+            EliteSpecialization::Berserker => Self::Berserker,
+            EliteSpecialization::Dragonhunter => Self::Dragonhunter,
+            EliteSpecialization::Reaper => Self::Reaper,
+            EliteSpecialization::Chronomancer => Self::Chronomancer,
+            EliteSpecialization::Scrapper => Self::Scrapper,
+            EliteSpecialization::Tempest => Self::Tempest,
+            EliteSpecialization::Herald => Self::Herald,
+            EliteSpecialization::Soulbeast => Self::Soulbeast,
+            EliteSpecialization::Weaver => Self::Weaver,
+            EliteSpecialization::Holosmith => Self::Holosmith,
+            EliteSpecialization::Deadeye => Self::Deadeye,
+            EliteSpecialization::Mirage => Self::Mirage,
+            EliteSpecialization::Scourge => Self::Scourge,
+            EliteSpecialization::Spellbreaker => Self::Spellbreaker,
+            EliteSpecialization::Firebrand => Self::Firebrand,
+            EliteSpecialization::Renegade => Self::Renegade,
+            EliteSpecialization::Harbinger => Self::Harbinger,
+            EliteSpecialization::Willbender => Self::Willbender,
+            EliteSpecialization::Virtuoso => Self::Virtuoso,
+            EliteSpecialization::Catalyst => Self::Catalyst,
+            EliteSpecialization::Bladesworn => Self::Bladesworn,
+            EliteSpecialization::Vindicator => Self::Vindicator,
+            EliteSpecialization::Mechanist => Self::Mechanist,
+            EliteSpecialization::Specter => Self::Specter,
+            EliteSpecialization::Untamed => Self::Untamed,
+            EliteSpecialization::Troubadour => Self::Troubadour,
+            EliteSpecialization::Paragon => Self::Paragon,
+            EliteSpecialization::Amalgam => Self::Amalgam,
+            EliteSpecialization::Ritualist => Self::Ritualist,
+            EliteSpecialization::Antiquary => Self::Antiquary,
+            EliteSpecialization::Galeshot => Self::Galeshot,
+            EliteSpecialization::Conduit => Self::Conduit,
+            EliteSpecialization::Evoker => Self::Evoker,
+            EliteSpecialization::Luminary => Self::Luminary,
+        }
+    }
+}
+
+impl TryFrom<Specialization> for EliteSpecialization {
+    type Error = Specialization;
+
+    #[inline]
+    fn try_from(spec: Specialization) -> Result<Self, Self::Error> {
+        Self::try_from(u32::from(spec)).map_err(|_| spec)
+    }
+}
+
+impl From<EliteSpecialization> for Profession {
+    #[inline]
+    fn from(elite: EliteSpecialization) -> Self {
+        elite.profession()
+    }
+}
+
+/// Mount index.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum MountIndex {
+    None = 0,
+    Jackal = 1,
+    Griffon = 2,
+    Springer = 3,
+    Skimmer = 4,
+    Raptor = 5,
+    RollerBeetle = 6,
+    Warclaw = 7,
+    Skyscale = 8,
+    Skiff = 9,
+    SiegeTurtle = 10,
+}
+
+impl MountIndex {
+    /// Checks whether a mount is used.
+    #[inline]
+    pub fn is_mounted(&self) -> bool {
+        *self != Self::None
+    }
+}
+
+/// Implements conversions to [`Profession`], [`Specialization`] & [`EliteSpecialization`]
+/// for a struct with raw profession & third specialization ids.
+macro_rules! impl_game_ids {
+    ( $ty:ty { $profession:ident, $specialization:ident } ) => {
+        impl TryFrom<&$ty> for $crate::game::Profession {
+            type Error = u32;
+
+            #[inline]
+            fn try_from(value: &$ty) -> Result<Self, Self::Error> {
+                value.$profession.try_into()
+            }
+        }
+
+        impl TryFrom<&$ty> for $crate::game::Specialization {
+            type Error = u32;
+
+            #[inline]
+            fn try_from(value: &$ty) -> Result<Self, Self::Error> {
+                value.$specialization.try_into()
+            }
+        }
+
+        impl TryFrom<&$ty> for $crate::game::EliteSpecialization {
+            type Error = u32;
+
+            #[inline]
+            fn try_from(value: &$ty) -> Result<Self, Self::Error> {
+                value.$specialization.try_into()
+            }
+        }
+    };
+}
+
+pub(crate) use impl_game_ids;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elite_round_trip() {
+        for id in 0..=u8::MAX as u32 {
+            let Ok(spec) = Specialization::try_from(id) else {
+                assert!(EliteSpecialization::try_from(id).is_err());
+                continue;
+            };
+            match EliteSpecialization::try_from(spec) {
+                Ok(elite) => {
+                    assert!(spec.is_elite());
+                    assert_eq!(Specialization::from(elite), spec);
+                    assert_eq!(u32::from(elite), id);
+                    assert_eq!(elite.profession(), spec.profession());
+                }
+                Err(err) => {
+                    assert!(!spec.is_elite());
+                    assert_eq!(err, spec);
+                }
+            }
+        }
+        assert_eq!(
+            Profession::from(EliteSpecialization::Luminary),
+            Profession::Guardian
+        );
+        assert_eq!(
+            Profession::from(EliteSpecialization::Troubadour),
+            Profession::Mesmer
+        );
+    }
+}
@@ -3,6 +3,8 @@
 use crate::{AddonApi, GameBindApi};
 
 /// Game keybinds.
+///
+/// Values match the game control ids, as used by Unofficial Extras.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(
@@ -227,6 +229,200 @@ pub enum GameBind {
     GearLoadout9 = 190,
 }
 
+impl TryFrom<i32> for GameBind {
+    type Error = i32;
+
+    fn try_from(id: i32) -> Result<Self, Self::Error> {
+        Ok(match id {
+            0 => Self::MoveForward,
+            1 => Self::MoveBackward,
+            2 => Self::MoveLeft,
+            3 => Self::MoveRight,
+            4 => Self::MoveTurnLeft,
+            5 => Self::MoveTurnRight,
+            6 => Self::MoveDodge,
+            7 => Self::MoveAutoRun,
+            8 => Self::MoveWalk,
+            9 => Self::MoveJump,
+            10 => Self::MoveSwimUp,
+            11 => Self::MoveSwimDown,
+            12 => Self::MoveAboutFace,
+            17 => Self::SkillWeaponSwap,
+            18 => Self::SkillWeapon1,
+            19 => Self::SkillWeapon2,
+            20 => Self::SkillWeapon3,
+            21 => Self::SkillWeapon4,
+            22 => Self::SkillWeapon5,
+            23 => Self::SkillHeal,
+            24 => Self::SkillUtility1,
+            25 => Self::SkillUtility2,
+            26 => Self::SkillUtility3,
+            27 => Self::SkillElite,
+            28 => Self::SkillProfession1,
+            29 => Self::SkillProfession2,
+            30 => Self::SkillProfession3,
+            31 => Self::SkillProfession4,
+            79 => Self::SkillProfession5,
+            201 => Self::SkillProfession6,
+            202 => Self::SkillProfession7,
+            82 => Self::SkillSpecialAction,
+            131 => Self::TargetAlert,
+            32 => Self::TargetCall,
+            33 => Self::TargetTake,
+            199 => Self::TargetCallLocal,
+            200 => Self::TargetTakeLocal,
+            34 => Self::TargetEnemyNearest,
+            35 => Self::TargetEnemyNext,
+            36 => Self::TargetEnemyPrev,
+            37 => Self::TargetAllyNearest,
+            38 => Self::TargetAllyNext,
+            39 => Self::TargetAllyPrev,
+            40 => Self::TargetLock,
+            80 => Self::TargetSnapGroundTarget,
+            115 => Self::TargetSnapGroundTargetToggle,
+            116 => Self::TargetAutoTargetingDisable,
+            117 => Self::TargetAutoTargetingToggle,
+            197 => Self::TargetAllyTargetingMode,
+            198 => Self::TargetAllyTargetingModeToggle,
+            41 => Self::UiTradingPost,
+            42 => Self::UiContacts,
+            43 => Self::UiGuild,
+            44 => Self::UiHero,
+            45 => Self::UiInventory,
+            46 => Self::UiPets,
+            47 => Self::UiLogout,
+            71 => Self::UiMail,
+            48 => Self::UiOptions,
+            49 => Self::UiParty,
+            73 => Self::UiPvp,
+            75 => Self::UiPvpBuild,
+            50 => Self::UiScoreboard,
+            209 => Self::UiWizardsVault,
+            51 => Self::UiInformation,
+            70 => Self::UiChatToggle,
+            52 => Self::UiChatCommand,
+            53 => Self::UiChatFocus,
+            54 => Self::UiChatReply,
+            55 => Self::UiToggle,
+            85 => Self::UiSquadBroadcastChatToggle,
+            83 => Self::UiSquadBroadcastChatCommand,
+            84 => Self::UiSquadBroadcastChatFocus,
+            13 => Self::CameraFree,
+            14 => Self::CameraZoomIn,
+            15 => Self::CameraZoomOut,
+            16 => Self::CameraReverse,
+            78 => Self::CameraActionMode,
+            114 => Self::CameraActionModeDisable,
+            56 => Self::ScreenshotNormal,
+            57 => Self::ScreenshotStereoscopic,
+            59 => Self::MapToggle,
+            60 => Self::MapFocusPlayer,
+            61 => Self::MapFloorDown,
+            62 => Self::MapFloorUp,
+            63 => Self::MapZoomIn,
+            64 => Self::MapZoomOut,
+            152 => Self::MountToggle,
+            130 => Self::MountMovement,
+            153 => Self::MountSecondaryMovement,
+            155 => Self::MountRaptor,
+            156 => Self::MountSpringer,
+            157 => Self::MountSkimmer,
+            158 => Self::MountJackal,
+            159 => Self::MountGriffon,
+            161 => Self::MountRollerBeetle,
+            169 => Self::MountWarclaw,
+            170 => Self::MountSkyscale,
+            203 => Self::MountSiegeTurtle,
+            102 => Self::SpectatorNearestFixed,
+            103 => Self::SpectatorNearestPlayer,
+            104 => Self::SpectatorPlayerRed1,
+            105 => Self::SpectatorPlayerRed2,
+            106 => Self::SpectatorPlayerRed3,
+            107 => Self::SpectatorPlayerRed4,
+            108 => Self::SpectatorPlayerRed5,
+            109 => Self::SpectatorPlayerBlue1,
+            110 => Self::SpectatorPlayerBlue2,
+            111 => Self::SpectatorPlayerBlue3,
+            112 => Self::SpectatorPlayerBlue4,
+            113 => Self::SpectatorPlayerBlue5,
+            120 => Self::SpectatorFreeCamera,
+            127 => Self::SpectatorFreeCameraMode,
+            121 => Self::SpectatorFreeMoveForward,
+            122 => Self::SpectatorFreeMoveBackward,
+            123 => Self::SpectatorFreeMoveLeft,
+            124 => Self::SpectatorFreeMoveRight,
+            125 => Self::SpectatorFreeMoveUp,
+            126 => Self::SpectatorFreeMoveDown,
+            86 => Self::SquadMarkerPlaceWorldArrow,
+            87 => Self::SquadMarkerPlaceWorldCircle,
+            88 => Self::SquadMarkerPlaceWorldHeart,
+            89 => Self::SquadMarkerPlaceWorldSquare,
+            90 => Self::SquadMarkerPlaceWorldStar,
+            91 => Self::SquadMarkerPlaceWorldSwirl,
+            92 => Self::SquadMarkerPlaceWorldTriangle,
+            93 => Self::SquadMarkerPlaceWorldCross,
+            119 => Self::SquadMarkerClearAllWorld,
+            94 => Self::SquadMarkerSetAgentArrow,
+            95 => Self::SquadMarkerSetAgentCircle,
+            96 => Self::SquadMarkerSetAgentHeart,
+            97 => Self::SquadMarkerSetAgentSquare,
+            98 => Self::SquadMarkerSetAgentStar,
+            99 => Self::SquadMarkerSetAgentSwirl,
+            100 => Self::SquadMarkerSetAgentTriangle,
+            101 => Self::SquadMarkerSetAgentCross,
+            118 => Self::SquadMarkerClearAllAgent,
+            196 => Self::MasteryAccess,
+            204 => Self::MasteryAccessFishing,
+            205 => Self::MasteryAccessSkiff,
+            206 => Self::MasteryAccessJadeBotWaypoint,
+            207 => Self::MasteryAccessRiftScan,
+            208 => Self::MasteryAccessSkyscale,
+            211 => Self::MasteryAccessHomesteadDoorway,
+            74 => Self::MiscAoELoot,
+            65 => Self::MiscInteract,
+            66 => Self::MiscShowEnemies,
+            67 => Self::MiscShowAllies,
+            68 => Self::MiscStowDrawWeapon,
+            69 => Self::MiscToggleLanguage,
+            76 => Self::MiscTogglePetCombat,
+            160 => Self::MiscToggleFullScreen,
+            210 => Self::MiscToggleDecorationMode,
+            162 => Self::ToyUseDefault,
+            163 => Self::ToyUseSlotChair,
+            164 => Self::ToyUseSlotInstrument,
+            165 => Self::ToyUseSlotHeltItem,
+            166 => Self::ToyUseSlotToy,
+            167 => Self::ToyUseSlotTonic,
+            171 => Self::Loadout1,
+            172 => Self::Loadout2,
+            173 => Self::Loadout3,
+            174 => Self::Loadout4,
+            175 => Self::Loadout5,
+            176 => Self::Loadout6,
+            177 => Self::Loadout7,
+            178 => Self::Loadout8,
+            179 => Self::Loadout9,
+            182 => Self::GearLoadout1,
+            183 => Self::GearLoadout2,
+            184 => Self::GearLoadout3,
+            185 => Self::GearLoadout4,
+            186 => Self::GearLoadout5,
+            187 => Self::GearLoadout6,
+            188 => Self::GearLoadout7,
+            189 => Self::GearLoadout8,
+            190 => Self::GearLoadout9,
+            _ => return Err(id),
+        })
+    }
+}
+
+impl From<GameBind> for i32 {
+    #[inline]
+    fn from(bind: GameBind) -> Self {
+        bind as i32
+    }
+}
+
 pub type RawGamebindPressAsync = unsafe extern "C-unwind" fn(game_bind: GameBind);
 
 pub type RawGamebindReleaseAsync = unsafe extern "C-unwind" fn(game_bind: GameBind);
@@ -280,3 +476,27 @@ pub fn is_gamebind_bound(bind: GameBind) -> bool {
     let GameBindApi { is_bound, .. } = AddonApi::get().game_bind;
     unsafe { is_bound(bind) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_id_round_trip() {
+        for id in -1..=u8::MAX as i32 {
+            if let Ok(bind) = GameBind::try_from(id) {
+                assert_eq!(i32::from(bind), id, "{bind:?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "strum")]
+    #[test]
+    fn all_variants_convert() {
+        use strum::VariantArray;
+
+        for bind in GameBind::VARIANTS {
+            assert_eq!(GameBind::try_from(i32::from(*bind)), Ok(*bind));
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! Mirror of the game keybinds.
+//!
+//! With the `"extras"` feature the actual keys bound to each [`GameBind`] are learned from Unofficial Extras [`KEYBIND_CHANGED`](crate::event::extras::KEYBIND_CHANGED) events.
+//! Without key information [`is_gamebind_bound`] is used as fallback to check whether a bind is set.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{gamebind::GameBind, keybind_mirror::key_name};
+//!
+//! # #[cfg(feature = "extras")]
+//! nexus::keybind_mirror::subscribe_keybind_mirror().revert_on_unload();
+//!
+//! let name: Option<String> = key_name(GameBind::SkillWeapon1);
+//! ```
+
+use crate::gamebind::{is_gamebind_bound, GameBind};
+use bitflags::bitflags;
+use std::{collections::BTreeMap, fmt, sync::Mutex};
+
+/// Shared keybind mirror.
+static MIRROR: Mutex<KeybindMirror> = Mutex::new(KeybindMirror::new());
+
+/// Input device of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum KeyDevice {
+    Mouse,
+    Keyboard,
+}
+
+bitflags! {
+    /// Key modifiers.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct KeyModifiers : u32 {
+        const Shift = 1 << 0;
+        const Ctrl = 1 << 1;
+        const Alt = 1 << 2;
+    }
+}
+
+/// Key bound to a game control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Key {
+    /// Input device of the key.
+    pub device: KeyDevice,
+
+    /// Game key code.
+    pub code: i32,
+
+    /// Modifiers held with the key.
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Creates a new key.
+    #[inline]
+    pub const fn new(device: KeyDevice, code: i32, modifiers: KeyModifiers) -> Self {
+        Self {
+            device,
+            code,
+            modifiers,
+        }
+    }
+
+    /// Returns the name of the key without modifiers.
+    pub fn key_name(&self) -> String {
+        match self.device {
+            KeyDevice::Mouse => match self.code {
+                0 => "Left Mouse".into(),
+                1 => "Middle Mouse".into(),
+                2 => "Right Mouse".into(),
+                code => format!("Mouse {}", code + 1),
+            },
+            KeyDevice::Keyboard => {
+                keyboard_name(self.code)
+                    .map(Into::into)
+                    .unwrap_or_else(|| match self.code {
+                        code @ 32..=43 => format!("F{}", code - 31),
+                        code @ (48..=57 | 65..=90) => char::from(code as u8).to_string(),
+                        code @ 92..=101 => format!("Numpad {}", code - 92),
+                        code => format!("Key {code}"),
+                    })
+            }
+        }
+    }
+
+    /// Returns the human-readable name of the key including modifiers, for example `"Ctrl + Shift + 1"`.
+    #[inline]
+    pub fn name(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::Ctrl, "Ctrl"),
+            (KeyModifiers::Alt, "Alt"),
+            (KeyModifiers::Shift, "Shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name} + ")?;
+            }
+        }
+        f.write_str(&self.key_name())
+    }
+}
+
+/// Returns the name of a keyboard key without a generic name.
+fn keyboard_name(code: i32) -> Option<&'static str> {
+    Some(match code {
+        0 => "Left Alt",
+        1 => "Left Ctrl",
+        2 => "Left Shift",
+        3 => "'",
+        4 => "#",
+        5 => "Caps Lock",
+        6 => ":",
+        7 => "-",
+        8 => "=",
+        9 => "Escape",
+        10 => "[",
+        11 => "Num Lock",
+        12 => ".",
+        13 => "]",
+        14 => ";",
+        15 => "/",
+        16 => "Print",
+        17 => "~",
+        18 => "Backspace",
+        19 => "Delete",
+        20 => "Enter",
+        21 => "Space",
+        22 => "Tab",
+        23 => "End",
+        24 => "Home",
+        25 => "Insert",
+        26 => "Page Down",
+        27 => "Page Up",
+        28 => "Down",
+        29 => "Left",
+        30 => "Right",
+        31 => "Up",
+        _ => return None,
+    })
+}
+
+/// Primary & secondary key of a game control.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keybind {
+    /// Primary key.
+    pub primary: Option<Key>,
+
+    /// Secondary key.
+    pub secondary: Option<Key>,
+}
+
+impl Keybind {
+    /// Returns the key at the given bind index, 0 being primary & 1 secondary.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<Key> {
+        match index {
+            0 => self.primary,
+            1 => self.secondary,
+            _ => None,
+        }
+    }
+
+    /// Checks whether any key is bound.
+    #[inline]
+    pub fn is_bound(&self) -> bool {
+        self.primary.is_some() || self.secondary.is_some()
+    }
+
+    /// Returns an iterator over the bound keys.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = Key> {
+        self.primary.into_iter().chain(self.secondary)
+    }
+}
+
+/// Table of keys bound to game controls.
+#[derive(Debug, Clone, Default)]
+pub struct KeybindMirror {
+    binds: BTreeMap<GameBind, Keybind>,
+}
+
+impl KeybindMirror {
+    /// Creates a new empty mirror.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            binds: BTreeMap::new(),
+        }
+    }
+
+    /// Processes an Unofficial Extras keybind change.
+    #[cfg(feature = "extras")]
+    pub fn process(&mut self, change: &arcdps::extras::keybinds::RawKeybindChange) {
+        use arcdps::extras::keybinds::DeviceType;
+
+        let Ok(bind) = GameBind::try_from(change.control as i32) else {
+            return;
+        };
+        let key = &change.single_key;
+        let device = match key.device_type {
+            DeviceType::Mouse => Some(KeyDevice::Mouse),
+            DeviceType::Keyboard => Some(KeyDevice::Keyboard),
+            _ => None,
+        };
+        let key = device.map(|device| {
+            Key::new(
+                device,
+                key.code,
+                KeyModifiers::from_bits_truncate(key.modifier as u32),
+            )
+        });
+        if let Ok(index) = change.bind_index.try_into() {
+            self.set(bind, index, key);
+        }
+    }
+
+    /// Sets the key at the given bind index, 0 being primary & 1 secondary.
+    pub fn set(&mut self, bind: GameBind, index: usize, key: Option<Key>) {
+        let keybind = self.binds.entry(bind).or_default();
+        match index {
+            0 => keybind.primary = key,
+            1 => keybind.secondary = key,
+            _ => {}
+        }
+    }
+
+    /// Returns the [`Keybind`] of the game control, if known.
+    #[inline]
+    pub fn get(&self, bind: GameBind) -> Option<&Keybind> {
+        self.binds.get(&bind)
+    }
+
+    /// Returns the primary key of the game control.
+    #[inline]
+    pub fn primary(&self, bind: GameBind) -> Option<Key> {
+        self.get(bind).and_then(|keybind| keybind.primary)
+    }
+
+    /// Returns the secondary key of the game control.
+    #[inline]
+    pub fn secondary(&self, bind: GameBind) -> Option<Key> {
+        self.get(bind).and_then(|keybind| keybind.secondary)
+    }
+
+    /// Returns the first bound key of the game control.
+    #[inline]
+    pub fn key(&self, bind: GameBind) -> Option<Key> {
+        self.get(bind).and_then(|keybind| keybind.keys().next())
+    }
+
+    /// Returns an iterator over all known keybinds.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (GameBind, &Keybind)> {
+        self.binds.iter().map(|(bind, keybind)| (*bind, keybind))
+    }
+
+    /// Clears all known keybinds.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.binds.clear()
+    }
+}
+
+/// Subscribes the shared [`KeybindMirror`] to Unofficial Extras keybind changes.
+///
+/// Returns a [`Revertible`](crate::Revertible) to revert the subscribe.
+#[cfg(feature = "extras")]
+pub fn subscribe_keybind_mirror() -> crate::Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    use crate::event::{event_consume, extras::KEYBIND_CHANGED};
+    use arcdps::extras::keybinds::RawKeybindChange;
+
+    KEYBIND_CHANGED.subscribe(event_consume!(<RawKeybindChange> |change| {
+        if let Some(change) = change {
+            with_keybind_mirror(|mirror| mirror.process(change));
+        }
+    }))
+}
+
+/// Accesses the shared [`KeybindMirror`].
+#[inline]
+pub fn with_keybind_mirror<R>(body: impl FnOnce(&mut KeybindMirror) -> R) -> R {
+    body(&mut MIRROR.lock().unwrap())
+}
+
+/// Returns the [`Keybind`] of the game control from the shared [`KeybindMirror`].
+#[inline]
+pub fn keybind(bind: GameBind) -> Option<Keybind> {
+    with_keybind_mirror(|mirror| mirror.get(bind).copied())
+}
+
+/// Returns the name of the first key bound to the game control.
+#[inline]
+pub fn key_name(bind: GameBind) -> Option<String> {
+    with_keybind_mirror(|mirror| mirror.key(bind)).map(|key| key.name())
+}
+
+/// Checks whether the game control is bound.
+///
+/// Falls back to [`is_gamebind_bound`] if the keys of the control are unknown.
+#[inline]
+pub fn is_bound(bind: GameBind) -> bool {
+    keybind(bind)
+        .map(|keybind| keybind.is_bound())
+        .unwrap_or_else(|| is_gamebind_bound(bind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names() {
+        let key = Key::new(
+            KeyDevice::Keyboard,
+            49,
+            KeyModifiers::Shift | KeyModifiers::Ctrl,
+        );
+        assert_eq!(key.name(), "Ctrl + Shift + 1");
+        assert_eq!(
+            Key::new(KeyDevice::Keyboard, 33, KeyModifiers::empty()).name(),
+            "F2"
+        );
+        assert_eq!(
+            Key::new(KeyDevice::Mouse, 3, KeyModifiers::Alt).name(),
+            "Alt + Mouse 4"
+        );
+
+        let mut mirror = KeybindMirror::new();
+        mirror.set(GameBind::SkillWeapon1, 1, Some(key));
+        assert_eq!(mirror.primary(GameBind::SkillWeapon1), None);
+        assert_eq!(mirror.key(GameBind::SkillWeapon1), Some(key));
+    }
+}
@@ -1,10 +1,17 @@
 //! Localization of strings.
 
 use crate::{
+    callbacks::Callbacks,
     util::{str_to_c, string_from_c},
-    AddonApi, LocalizationApi,
+    AddonApi, LocalizationApi, Revertible,
+};
+use std::{
+    ffi::c_char,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
 };
-use std::ffi::c_char;
 
 pub type RawLocalizationTranslate =
     unsafe extern "C-unwind" fn(identifier: *const c_char) -> *const c_char;
@@ -61,3 +68,270 @@ pub fn set_translation(
     let string = str_to_c(string, "failed to convert translation string");
     unsafe { set(identifier.as_ptr(), language.as_ptr(), string.as_ptr()) }
 }
+
+/// Current game language.
+static GAME_LANGUAGE: Mutex<Option<Language>> = Mutex::new(None);
+
+/// Whether addon strings follow the game language.
+static FOLLOW_GAME: AtomicBool = AtomicBool::new(false);
+
+/// Registered active language change callbacks.
+static CALLBACKS: Callbacks<Option<Language>> = Callbacks::new();
+
+/// Game client language with a Nexus locale.
+///
+/// The game has no Korean client, so its legacy Korean language value is not supported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+    Chinese,
+}
+
+impl Language {
+    /// Returns the Nexus language identifier of the language.
+    #[inline]
+    pub const fn identifier(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::French => "fr",
+            Self::German => "de",
+            Self::Spanish => "es",
+            Self::Chinese => "cn",
+        }
+    }
+
+    /// Returns the language for a Nexus language identifier.
+    #[inline]
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Some(match identifier {
+            "en" => Self::English,
+            "fr" => Self::French,
+            "de" => Self::German,
+            "es" => Self::Spanish,
+            "cn" => Self::Chinese,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "extras")]
+impl From<arcdps::Language> for Language {
+    #[inline]
+    fn from(language: arcdps::Language) -> Self {
+        match language {
+            arcdps::Language::English => Self::English,
+            arcdps::Language::French => Self::French,
+            arcdps::Language::German => Self::German,
+            arcdps::Language::Spanish => Self::Spanish,
+            arcdps::Language::Chinese => Self::Chinese,
+        }
+    }
+}
+
+#[cfg(feature = "rtapi")]
+impl TryFrom<crate::rtapi::GameLanguage> for Language {
+    type Error = crate::rtapi::GameLanguage;
+
+    #[inline]
+    fn try_from(language: crate::rtapi::GameLanguage) -> Result<Self, Self::Error> {
+        use crate::rtapi::GameLanguage;
+
+        Ok(match language {
+            GameLanguage::English => Self::English,
+            GameLanguage::French => Self::French,
+            GameLanguage::German => Self::German,
+            GameLanguage::Spanish => Self::Spanish,
+            GameLanguage::Chinese => Self::Chinese,
+            GameLanguage::Korean => return Err(language),
+        })
+    }
+}
+
+/// Subscribes to game language updates from all available sources.
+///
+/// Uses Unofficial Extras [`LANGUAGE_CHANGED`](crate::event::extras::LANGUAGE_CHANGED) with the `"extras"` feature
+/// and polls the RealTime API every frame with the `"rtapi"` feature.
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+pub fn subscribe_game_language() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    #[cfg(feature = "extras")]
+    let extras = {
+        use crate::event::{event_consume, extras::LANGUAGE_CHANGED};
+
+        LANGUAGE_CHANGED
+            .subscribe(event_consume!(<arcdps::Language> |language| {
+                if let Some(language) = language {
+                    set_game_language((*language).into());
+                }
+            }))
+            .into_inner()
+    };
+
+    #[cfg(feature = "rtapi")]
+    let rtapi = {
+        use crate::gui::{register_render, RenderType};
+
+        extern "C-unwind" fn poll_game_language() {
+            if let Some(language) = crate::rtapi::RealTimeApi::get()
+                .and_then(|rtapi| rtapi.read_game())
+                .and_then(|game| game.language.ok())
+                .and_then(|language| Language::try_from(language).ok())
+            {
+                set_game_language(language);
+            }
+        }
+
+        poll_game_language();
+        register_render(RenderType::PreRender, poll_game_language).into_inner()
+    };
+
+    let revert = move || {
+        #[cfg(feature = "extras")]
+        extras();
+        #[cfg(feature = "rtapi")]
+        rtapi();
+    };
+    revert.into()
+}
+
+/// Returns the current game language, if known.
+#[inline]
+pub fn game_language() -> Option<Language> {
+    *GAME_LANGUAGE.lock().unwrap()
+}
+
+/// Sets the current game language.
+///
+/// Notifies [active language callbacks](on_active_language_change) if following the game language.
+pub fn set_game_language(language: Language) {
+    let previous = GAME_LANGUAGE.lock().unwrap().replace(language);
+    if previous != Some(language) && follows_game_language() {
+        CALLBACKS.invoke(&Some(language));
+    }
+}
+
+/// Sets whether addon strings follow the game language instead of the Nexus language setting.
+///
+/// Notifies [active language callbacks](on_active_language_change) if the active language changes.
+pub fn set_follow_game_language(follow: bool) {
+    let previous = FOLLOW_GAME.swap(follow, Ordering::Relaxed);
+    if previous != follow && game_language().is_some() {
+        CALLBACKS.invoke(&active_language());
+    }
+}
+
+/// Registers a callback invoked when the active language of addon strings changes.
+///
+/// The callback receives the new [active language](active_language).
+/// Callbacks are local to the addon, other addons following the game language are not notified.
+///
+/// Returns a [`Revertible`] to remove the callback.
+pub fn on_active_language_change(
+    callback: impl Fn(&Option<Language>) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register((), callback)
+}
+
+/// Returns whether addon strings follow the game language.
+#[inline]
+pub fn follows_game_language() -> bool {
+    FOLLOW_GAME.load(Ordering::Relaxed)
+}
+
+/// Returns the language addon strings are translated to.
+///
+/// Returns [`None`] if the Nexus language setting is used.
+#[inline]
+pub fn active_language() -> Option<Language> {
+    if follows_game_language() {
+        game_language()
+    } else {
+        None
+    }
+}
+
+/// Attempts to translate the identifier into the active addon language.
+///
+/// Uses the game language if [following](set_follow_game_language) it, otherwise the Nexus language setting.
+/// Returns the same identifier if not available.
+#[inline]
+pub fn translate_active(identifier: impl AsRef<str>) -> Option<String> {
+    match active_language() {
+        Some(language) => translate_to(identifier, language.identifier()),
+        None => translate(identifier),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifiers() {
+        for (language, identifier) in [
+            (Language::English, "en"),
+            (Language::French, "fr"),
+            (Language::German, "de"),
+            (Language::Spanish, "es"),
+            (Language::Chinese, "cn"),
+        ] {
+            assert_eq!(language.identifier(), identifier);
+            assert_eq!(Language::from_identifier(identifier), Some(language));
+        }
+        assert_eq!(Language::from_identifier("zh"), None);
+        assert_eq!(Language::from_identifier("ko"), None);
+    }
+
+    #[cfg(feature = "extras")]
+    #[test]
+    fn extras_identifiers() {
+        for (language, identifier) in [
+            (arcdps::Language::English, "en"),
+            (arcdps::Language::French, "fr"),
+            (arcdps::Language::German, "de"),
+            (arcdps::Language::Spanish, "es"),
+            (arcdps::Language::Chinese, "cn"),
+        ] {
+            assert_eq!(Language::from(language).identifier(), identifier);
+        }
+    }
+
+    #[cfg(feature = "rtapi")]
+    #[test]
+    fn rtapi_identifiers() {
+        use crate::rtapi::GameLanguage;
+
+        for (language, identifier) in [
+            (GameLanguage::English, "en"),
+            (GameLanguage::French, "fr"),
+            (GameLanguage::German, "de"),
+            (GameLanguage::Spanish, "es"),
+            (GameLanguage::Chinese, "cn"),
+        ] {
+            assert_eq!(
+                Language::try_from(language).unwrap().identifier(),
+                identifier
+            );
+        }
+        assert_eq!(
+            Language::try_from(GameLanguage::Korean),
+            Err(GameLanguage::Korean)
+        );
+    }
+}
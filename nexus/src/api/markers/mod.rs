@@ -0,0 +1,445 @@
+//! TacO marker pack loading & rendering.
+//!
+//! Unpacked marker packs are loaded from subdirectories, for example of the addon directory.
+//! Markers on the current map are projected to the screen using [`Projection`] and drawn on the imgui background draw list.
+//! The current map & camera are read from the RealTime API, falling back to the Mumble link with the `"mumble"` feature.
+//!
+//! Enable the `"markers"` feature for marker pack support.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{markers::{load_marker_packs, show_markers}, paths::get_addon_dir};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! if let Some(dir) = get_addon_dir("MyAddon") {
+//!     load_marker_packs(dir.join("markers"))?;
+//! }
+//! show_markers().revert_on_unload();
+//! # Ok(())
+//! # }
+//! ```
+
+mod pack;
+
+pub use self::pack::*;
+pub use crate::{
+    projection::current_camera,
+    trail::{TrailData, TRAIL_VERSION},
+};
+
+use crate::{
+    data_link::read_nexus_link,
+    gui::{register_render, RenderType},
+    imgui::{TreeNodeFlags, Ui},
+    projection::{Projection, Units},
+    render,
+    revertible::Revertible,
+    rtapi::RealTimeApi,
+    texture::{get_texture, load_texture_from_file, Texture},
+};
+use std::{collections::BTreeSet, io, path::Path, sync::Mutex};
+
+/// Shared marker packs & category toggles.
+static MARKERS: Mutex<Markers> = Mutex::new(Markers::new());
+
+/// Shared overlay configuration.
+static CONFIG: Mutex<MarkerConfig> = Mutex::new(MarkerConfig::new());
+
+/// Loaded marker packs.
+#[derive(Debug)]
+struct Markers {
+    packs: Vec<MarkerPack>,
+    disabled: BTreeSet<String>,
+    known: BTreeSet<String>,
+    requested_textures: BTreeSet<String>,
+}
+
+impl Markers {
+    const fn new() -> Self {
+        Self {
+            packs: Vec::new(),
+            disabled: BTreeSet::new(),
+            known: BTreeSet::new(),
+            requested_textures: BTreeSet::new(),
+        }
+    }
+
+    /// Adds a marker pack, replacing a loaded pack with the same name.
+    fn add(&mut self, pack: MarkerPack) {
+        for path in pack.default_disabled() {
+            if !self.known.contains(&path) {
+                self.disabled.insert(path);
+            }
+        }
+        self.known.extend(pack.category_paths());
+        self.packs.retain(|loaded| loaded.name != pack.name);
+        self.packs.push(pack);
+    }
+}
+
+/// Marker overlay configuration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerConfig {
+    /// Size of the drawn icons in pixels, multiplied by the marker icon size & UI scaling.
+    pub icon_size: f32,
+
+    /// Color of markers without a loaded icon.
+    pub fallback_color: [f32; 4],
+
+    /// Maximum distance to the camera to draw markers at, in meters.
+    pub max_distance: Option<f32>,
+
+    /// Whether to draw trails.
+    pub show_trails: bool,
+
+    /// Color of the drawn trails.
+    pub trail_color: [f32; 4],
+
+    /// Thickness of the drawn trails in pixels, multiplied by the UI scaling.
+    pub trail_thickness: f32,
+
+    /// Whether to hide the markers outside of gameplay.
+    pub gameplay_only: bool,
+}
+
+impl MarkerConfig {
+    /// Creates the default configuration.
+    pub const fn new() -> Self {
+        Self {
+            icon_size: 32.0,
+            fallback_color: [1.0, 0.85, 0.3, 0.9],
+            max_distance: Some(200.0),
+            show_trails: true,
+            trail_color: [0.3, 0.8, 1.0, 0.7],
+            trail_thickness: 3.0,
+            gameplay_only: true,
+        }
+    }
+}
+
+impl Default for MarkerConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the current marker overlay configuration.
+#[inline]
+pub fn marker_config() -> MarkerConfig {
+    CONFIG.lock().unwrap().clone()
+}
+
+/// Sets the marker overlay configuration.
+#[inline]
+pub fn set_marker_config(config: MarkerConfig) {
+    *CONFIG.lock().unwrap() = config;
+}
+
+/// Loads all unpacked marker packs in the subdirectories of a directory.
+///
+/// Returns the number of loaded packs.
+pub fn load_marker_packs(dir: impl AsRef<Path>) -> io::Result<usize> {
+    let packs = MarkerPack::load_all(dir)?;
+    let count = packs.len();
+    for pack in packs {
+        add_marker_pack(pack);
+    }
+    Ok(count)
+}
+
+/// Adds a marker pack, replacing a loaded pack with the same name.
+///
+/// Categories disabled by default in the pack are disabled, unless they were seen before.
+/// This keeps categories enabled by the user when a pack is reloaded.
+#[inline]
+pub fn add_marker_pack(pack: MarkerPack) {
+    MARKERS.lock().unwrap().add(pack)
+}
+
+/// Removes the marker pack with the given name.
+#[inline]
+pub fn remove_marker_pack(name: &str) -> Option<MarkerPack> {
+    let mut markers = MARKERS.lock().unwrap();
+    let index = markers.packs.iter().position(|pack| pack.name == name)?;
+    Some(markers.packs.remove(index))
+}
+
+/// Removes all marker packs.
+#[inline]
+pub fn clear_marker_packs() {
+    MARKERS.lock().unwrap().packs.clear()
+}
+
+/// Accesses the loaded marker packs.
+#[inline]
+pub fn with_marker_packs<R>(body: impl FnOnce(&[MarkerPack]) -> R) -> R {
+    body(&MARKERS.lock().unwrap().packs)
+}
+
+/// Checks whether a category is enabled.
+///
+/// A category is disabled if it or any of its parents are disabled.
+#[inline]
+pub fn is_category_enabled(path: &str) -> bool {
+    is_enabled(&MARKERS.lock().unwrap().disabled, path)
+}
+
+/// Enables or disables a category by its full path.
+#[inline]
+pub fn set_category_enabled(path: &str, enabled: bool) {
+    set_enabled(&mut MARKERS.lock().unwrap().disabled, path, enabled)
+}
+
+/// Returns the full paths of all disabled categories.
+///
+/// Can be used to persist the category toggles.
+#[inline]
+pub fn disabled_categories() -> Vec<String> {
+    MARKERS.lock().unwrap().disabled.iter().cloned().collect()
+}
+
+/// Sets the disabled categories by their full paths.
+#[inline]
+pub fn set_disabled_categories(paths: impl IntoIterator<Item = impl AsRef<str>>) {
+    MARKERS.lock().unwrap().disabled = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_lowercase())
+        .collect();
+}
+
+/// Returns the full paths of all categories seen in added packs.
+///
+/// Can be persisted alongside the [disabled categories](disabled_categories),
+/// so default toggles of packs are only applied to new categories.
+#[inline]
+pub fn known_categories() -> Vec<String> {
+    MARKERS.lock().unwrap().known.iter().cloned().collect()
+}
+
+/// Sets the categories seen in added packs by their full paths.
+#[inline]
+pub fn set_known_categories(paths: impl IntoIterator<Item = impl AsRef<str>>) {
+    MARKERS.lock().unwrap().known = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_lowercase())
+        .collect();
+}
+
+fn is_enabled(disabled: &BTreeSet<String>, path: &str) -> bool {
+    !path
+        .match_indices('.')
+        .map(|(index, _)| &path[..index])
+        .chain([path])
+        .any(|prefix| disabled.contains(prefix))
+}
+
+fn set_enabled(disabled: &mut BTreeSet<String>, path: &str, enabled: bool) {
+    let path = path.to_lowercase();
+    if enabled {
+        disabled.remove(&path);
+    } else {
+        disabled.insert(path);
+    }
+}
+
+/// Returns the current map id.
+///
+/// Uses the RealTime API if available, otherwise the Mumble link with the `"mumble"` feature.
+pub fn current_map_id() -> Option<u32> {
+    if let Some(world) = RealTimeApi::get().and_then(|rtapi| rtapi.read_world()) {
+        return Some(world.map_id);
+    }
+
+    #[cfg(feature = "mumble")]
+    if let Some(link) = crate::data_link::read_mumble_link() {
+        return Some(link.context.map_id);
+    }
+
+    None
+}
+
+/// Shows the marker overlay & the category toggles in the options.
+///
+/// Returns a [`Revertible`] to hide the overlay & options again.
+pub fn show_markers() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let overlay = register_render(RenderType::Render, render!(render_markers)).into_inner();
+    let options =
+        register_render(RenderType::OptionsRender, render!(render_marker_options)).into_inner();
+    let revert = move || {
+        overlay();
+        options();
+    };
+    revert.into()
+}
+
+/// Draws the markers of enabled categories on the current map.
+///
+/// Can be used directly from a custom render callback instead of [`show_markers`].
+pub fn draw_markers(ui: &Ui, config: &MarkerConfig) {
+    let Some(link) = read_nexus_link() else {
+        return;
+    };
+    if config.gameplay_only && !link.is_gameplay {
+        return;
+    }
+    let Some(camera) = current_camera() else {
+        return;
+    };
+    let Some(map_id) = current_map_id() else {
+        return;
+    };
+
+    let projection = Projection::from_link(&camera, &link);
+    let draw_list = ui.get_background_draw_list();
+    let mut markers = MARKERS.lock().unwrap();
+    let Markers {
+        packs,
+        disabled,
+        requested_textures,
+        ..
+    } = &mut *markers;
+
+    for pack in packs.iter() {
+        if config.show_trails {
+            for trail in pack
+                .trails_on_map(map_id)
+                .filter(|trail| is_enabled(disabled, &trail.category))
+            {
+                for segment in trail.data.segments() {
+                    for points in segment.windows(2) {
+                        let start = projection.project_unclipped(points[0], Units::Meters);
+                        let end = projection.project_unclipped(points[1], Units::Meters);
+                        if let (Some(start), Some(end)) = (start, end) {
+                            draw_list
+                                .add_line(start, end, config.trail_color)
+                                .thickness(config.trail_thickness * link.scaling)
+                                .build();
+                        }
+                    }
+                }
+            }
+        }
+
+        for poi in pack
+            .pois_on_map(map_id)
+            .filter(|poi| is_enabled(disabled, &poi.category))
+        {
+            let distance = projection.camera_distance(poi.position, Units::Meters);
+            if config.max_distance.is_some_and(|max| distance > max) {
+                continue;
+            }
+            let Some([x, y]) = projection.project(poi.position, Units::Meters) else {
+                continue;
+            };
+
+            let size = 0.5 * config.icon_size * pack.icon_size(poi) * link.scaling;
+            match pack
+                .icon_file(poi)
+                .and_then(|file| marker_texture(requested_textures, pack, file))
+            {
+                Some(texture) => draw_list
+                    .add_image(texture.id(), [x - size, y - size], [x + size, y + size])
+                    .build(),
+                None => draw_list
+                    .add_circle([x, y], 0.5 * size, config.fallback_color)
+                    .filled(true)
+                    .build(),
+            }
+        }
+    }
+}
+
+/// Returns the texture of a marker icon, loading it on first use.
+fn marker_texture(
+    requested: &mut BTreeSet<String>,
+    pack: &MarkerPack,
+    file: &str,
+) -> Option<Texture> {
+    let identifier = format!("MARKER_{}_{}", pack.name, file);
+    let texture = get_texture(&identifier);
+    if texture.is_none() && requested.insert(identifier.clone()) {
+        load_texture_from_file(&identifier, pack.root.join(file), None);
+    }
+    texture
+}
+
+/// Renders the category toggles of all marker packs.
+///
+/// Can be used directly from a custom options render callback instead of [`show_markers`].
+pub fn render_marker_options(ui: &Ui) {
+    let mut markers = MARKERS.lock().unwrap();
+    let Markers {
+        packs, disabled, ..
+    } = &mut *markers;
+
+    if packs.is_empty() {
+        ui.text_disabled("No marker packs loaded");
+    }
+    for pack in packs.iter() {
+        let _id = ui.push_id(pack.name.as_str());
+        if ui.collapsing_header(&pack.name, TreeNodeFlags::empty()) {
+            render_categories(ui, &pack.categories, "", disabled);
+        }
+    }
+}
+
+fn render_categories(
+    ui: &Ui,
+    categories: &[Category],
+    prefix: &str,
+    disabled: &mut BTreeSet<String>,
+) {
+    for category in categories {
+        let path = join(prefix, &category.name);
+        if category.is_separator {
+            ui.text_disabled(&category.display_name);
+            continue;
+        }
+
+        let _id = ui.push_id(path.as_str());
+        let mut enabled = !disabled.contains(&path);
+        if ui.checkbox(&category.display_name, &mut enabled) {
+            set_enabled(disabled, &path, enabled);
+        }
+        if enabled && !category.children.is_empty() {
+            ui.indent();
+            render_categories(ui, &category.children, &path, disabled);
+            ui.unindent();
+        }
+    }
+}
+
+fn render_markers(ui: &Ui) {
+    let config = marker_config();
+    draw_markers(ui, &config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_toggles_only_for_new_categories() {
+        let pack = || {
+            let mut pack = MarkerPack::new("Test", "");
+            pack.parse_xml(
+                r#"<OverlayData>
+                    <MarkerCategory name="Tactics" defaulttoggle="0" />
+                </OverlayData>"#,
+            )
+            .unwrap();
+            pack
+        };
+
+        let mut markers = Markers::new();
+        markers.add(pack());
+        assert!(!is_enabled(&markers.disabled, "tactics"));
+
+        set_enabled(&mut markers.disabled, "tactics", true);
+        markers.add(pack());
+        assert!(is_enabled(&markers.disabled, "tactics"));
+        assert_eq!(markers.packs.len(), 1);
+    }
+}
@@ -0,0 +1,500 @@
+//! TacO marker pack parsing.
+
+use super::TrailData;
+use crate::vec3::Vec3;
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Marker category.
+///
+/// Category names are stored lowercase, as TacO compares them case-insensitively.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Category {
+    /// Name of the category, without parent names.
+    pub name: String,
+
+    /// Name displayed in the UI.
+    pub display_name: String,
+
+    /// Icon file of markers in this category, relative to the pack root.
+    pub icon_file: Option<String>,
+
+    /// Icon size multiplier of markers in this category.
+    pub icon_size: Option<f32>,
+
+    /// Whether the category is only a separator in the UI.
+    pub is_separator: bool,
+
+    /// Whether the category is enabled by default.
+    pub default_toggle: bool,
+
+    /// Child categories.
+    pub children: Vec<Category>,
+}
+
+impl Category {
+    /// Creates a new category with the given name.
+    #[inline]
+    pub fn new(name: impl AsRef<str>) -> Self {
+        let name = name.as_ref().to_lowercase();
+        Self {
+            display_name: name.clone(),
+            name,
+            icon_file: None,
+            icon_size: None,
+            is_separator: false,
+            default_toggle: true,
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns the child category with the given name.
+    #[inline]
+    pub fn child(&self, name: &str) -> Option<&Self> {
+        find(&self.children, name)
+    }
+
+    /// Merges the attributes of another definition of the same category.
+    fn merge(&mut self, other: Self) {
+        if other.display_name != other.name {
+            self.display_name = other.display_name;
+        }
+        self.icon_file = other.icon_file.or(self.icon_file.take());
+        self.icon_size = other.icon_size.or(self.icon_size);
+        self.is_separator |= other.is_separator;
+        self.default_toggle &= other.default_toggle;
+    }
+}
+
+/// Point of interest marker.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Poi {
+    /// Map id of the marker.
+    pub map_id: u32,
+
+    /// Position of the marker in meters.
+    pub position: Vec3,
+
+    /// Full category path, for example `"tactics.mechanics"`.
+    pub category: String,
+
+    /// Icon file, relative to the pack root.
+    ///
+    /// Inherited from the category if [`None`].
+    pub icon_file: Option<String>,
+
+    /// Icon size multiplier.
+    ///
+    /// Inherited from the category if [`None`].
+    pub icon_size: Option<f32>,
+
+    /// Unique id of the marker.
+    pub guid: Option<String>,
+}
+
+/// Trail marker.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trail {
+    /// Full category path.
+    pub category: String,
+
+    /// Trail file, relative to the pack root.
+    pub file: String,
+
+    /// Texture file, relative to the pack root.
+    pub texture: Option<String>,
+
+    /// Unique id of the trail.
+    pub guid: Option<String>,
+
+    /// Trail points, read from the trail file.
+    pub data: TrailData,
+}
+
+impl Trail {
+    /// Returns the map id of the trail.
+    #[inline]
+    pub fn map_id(&self) -> u32 {
+        self.data.map_id
+    }
+}
+
+/// Unpacked TacO marker pack.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerPack {
+    /// Name of the pack.
+    pub name: String,
+
+    /// Root directory of the pack.
+    pub root: PathBuf,
+
+    /// Category tree.
+    pub categories: Vec<Category>,
+
+    /// Point of interest markers.
+    pub pois: Vec<Poi>,
+
+    /// Trail markers.
+    pub trails: Vec<Trail>,
+}
+
+impl MarkerPack {
+    /// Creates a new empty marker pack.
+    #[inline]
+    pub fn new(name: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            root: root.into(),
+            categories: Vec::new(),
+            pois: Vec::new(),
+            trails: Vec::new(),
+        }
+    }
+
+    /// Loads an unpacked marker pack from a directory.
+    ///
+    /// All XML files in the directory are parsed.
+    /// Trails with missing or invalid trail files are skipped.
+    pub fn load(root: impl AsRef<Path>) -> io::Result<Self> {
+        let root = root.as_ref();
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut pack = Self::new(name, root);
+
+        let mut files = fs::read_dir(root)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        files.sort();
+        for file in files {
+            let is_xml = file
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+            if is_xml && file.is_file() {
+                pack.parse_xml(&fs::read_to_string(file)?)?;
+            }
+        }
+
+        pack.trails
+            .retain_mut(|trail| match TrailData::load(root.join(&trail.file)) {
+                Ok(data) => {
+                    trail.data = data;
+                    true
+                }
+                Err(_) => false,
+            });
+        Ok(pack)
+    }
+
+    /// Loads all unpacked marker packs in the subdirectories of a directory.
+    ///
+    /// Packs failing to load are skipped, with the `"log"` feature a warning is logged.
+    pub fn load_all(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let mut packs = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                match Self::load(&path) {
+                    Ok(pack) => packs.push(pack),
+                    Err(_err) => {
+                        #[cfg(feature = "log")]
+                        ::log::warn!("skipping marker pack {}: {_err}", path.display());
+                    }
+                }
+            }
+        }
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(packs)
+    }
+
+    /// Parses a TacO XML document into the pack.
+    ///
+    /// Categories are merged with already defined categories.
+    /// Trail files are not read, trail data is left empty.
+    pub fn parse_xml(&mut self, xml: &str) -> io::Result<()> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(true);
+
+        let mut path = Vec::<String>::new();
+        loop {
+            match reader.read_event().map_err(invalid_data)? {
+                Event::Start(element) => {
+                    if is_element(&element, "markercategory") {
+                        let category = parse_category(&element)?;
+                        let name = category.name.clone();
+                        insert(&mut self.categories, &path, category);
+                        path.push(name);
+                    } else {
+                        self.parse_marker(&element)?;
+                    }
+                }
+                Event::Empty(element) => {
+                    if is_element(&element, "markercategory") {
+                        insert(&mut self.categories, &path, parse_category(&element)?);
+                    } else {
+                        self.parse_marker(&element)?;
+                    }
+                }
+                Event::End(element) => {
+                    if element
+                        .name()
+                        .as_ref()
+                        .eq_ignore_ascii_case(b"markercategory")
+                    {
+                        path.pop();
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_marker(&mut self, element: &BytesStart) -> io::Result<()> {
+        if is_element(element, "poi") {
+            let mut attributes = attributes(element)?;
+            let coord = |name| parse(&attributes, name).unwrap_or(0.0);
+            self.pois.push(Poi {
+                map_id: parse(&attributes, "mapid").unwrap_or(0),
+                position: Vec3::new(coord("xpos"), coord("ypos"), coord("zpos")),
+                category: attributes.remove("type").unwrap_or_default().to_lowercase(),
+                icon_file: attributes.remove("iconfile"),
+                icon_size: parse(&attributes, "iconsize"),
+                guid: attributes.remove("guid"),
+            });
+        } else if is_element(element, "trail") {
+            let mut attributes = attributes(element)?;
+            if let Some(file) = attributes.remove("traildata") {
+                self.trails.push(Trail {
+                    category: attributes.remove("type").unwrap_or_default().to_lowercase(),
+                    file,
+                    texture: attributes.remove("texture"),
+                    guid: attributes.remove("guid"),
+                    data: TrailData::default(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the category with the given full path.
+    pub fn category(&self, path: &str) -> Option<&Category> {
+        let mut names = path.split('.');
+        let first = find(&self.categories, names.next()?)?;
+        names.try_fold(first, |category, name| category.child(name))
+    }
+
+    /// Returns the categories along the given full path, starting at the root.
+    pub fn category_chain(&self, path: &str) -> Vec<&Category> {
+        let mut chain = Vec::new();
+        let mut categories = &self.categories;
+        for name in path.split('.') {
+            match find(categories, name) {
+                Some(category) => {
+                    chain.push(category);
+                    categories = &category.children;
+                }
+                None => break,
+            }
+        }
+        chain
+    }
+
+    /// Returns the icon file of a marker, inherited from its categories if unset.
+    pub fn icon_file<'a>(&'a self, poi: &'a Poi) -> Option<&'a str> {
+        poi.icon_file.as_deref().or_else(|| {
+            self.category_chain(&poi.category)
+                .into_iter()
+                .rev()
+                .find_map(|category| category.icon_file.as_deref())
+        })
+    }
+
+    /// Returns the icon size of a marker, inherited from its categories if unset.
+    pub fn icon_size(&self, poi: &Poi) -> f32 {
+        poi.icon_size
+            .or_else(|| {
+                self.category_chain(&poi.category)
+                    .into_iter()
+                    .rev()
+                    .find_map(|category| category.icon_size)
+            })
+            .unwrap_or(1.0)
+    }
+
+    /// Returns an iterator over the point of interest markers on a map.
+    #[inline]
+    pub fn pois_on_map(&self, map_id: u32) -> impl Iterator<Item = &Poi> {
+        self.pois.iter().filter(move |poi| poi.map_id == map_id)
+    }
+
+    /// Returns an iterator over the trail markers on a map.
+    #[inline]
+    pub fn trails_on_map(&self, map_id: u32) -> impl Iterator<Item = &Trail> {
+        self.trails
+            .iter()
+            .filter(move |trail| trail.map_id() == map_id)
+    }
+
+    /// Returns the full paths of all categories.
+    #[inline]
+    pub fn category_paths(&self) -> Vec<String> {
+        self.collect_paths(|_| true)
+    }
+
+    /// Returns the full paths of all categories disabled by default.
+    #[inline]
+    pub fn default_disabled(&self) -> Vec<String> {
+        self.collect_paths(|category| !category.default_toggle)
+    }
+
+    fn collect_paths(&self, filter: impl Fn(&Category) -> bool) -> Vec<String> {
+        fn visit(
+            categories: &[Category],
+            prefix: &str,
+            filter: &impl Fn(&Category) -> bool,
+            result: &mut Vec<String>,
+        ) {
+            for category in categories {
+                let path = join(prefix, &category.name);
+                if filter(category) {
+                    result.push(path.clone());
+                }
+                visit(&category.children, &path, filter, result);
+            }
+        }
+
+        let mut result = Vec::new();
+        visit(&self.categories, "", &filter, &mut result);
+        result
+    }
+}
+
+/// Joins a category path prefix & name.
+#[inline]
+pub(crate) fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.into()
+    } else {
+        format!("{prefix}.{name}")
+    }
+}
+
+fn find<'a>(categories: &'a [Category], name: &str) -> Option<&'a Category> {
+    categories
+        .iter()
+        .find(|category| category.name.eq_ignore_ascii_case(name))
+}
+
+fn insert(categories: &mut Vec<Category>, path: &[String], category: Category) {
+    let mut categories = categories;
+    for name in path {
+        let Some(index) = categories.iter().position(|parent| parent.name == *name) else {
+            return;
+        };
+        categories = &mut categories[index].children;
+    }
+    match categories
+        .iter_mut()
+        .find(|existing| existing.name == category.name)
+    {
+        Some(existing) => existing.merge(category),
+        None => categories.push(category),
+    }
+}
+
+fn parse_category(element: &BytesStart) -> io::Result<Category> {
+    let mut attributes = attributes(element)?;
+    let mut category = Category::new(attributes.remove("name").unwrap_or_default());
+    if let Some(display_name) = attributes.remove("displayname") {
+        category.display_name = display_name;
+    }
+    category.icon_file = attributes.remove("iconfile");
+    category.icon_size = parse(&attributes, "iconsize");
+    category.is_separator = attributes
+        .get("isseparator")
+        .is_some_and(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+    category.default_toggle = attributes.get("defaulttoggle").map_or(true, |value| {
+        value != "0" && !value.eq_ignore_ascii_case("false")
+    });
+    Ok(category)
+}
+
+fn is_element(element: &BytesStart, name: &str) -> bool {
+    element
+        .name()
+        .as_ref()
+        .eq_ignore_ascii_case(name.as_bytes())
+}
+
+/// Collects the attributes of an element with lowercase names.
+fn attributes(element: &BytesStart) -> io::Result<BTreeMap<String, String>> {
+    element
+        .attributes()
+        .map(|attribute| {
+            let attribute = attribute.map_err(invalid_data)?;
+            let name = String::from_utf8_lossy(attribute.key.as_ref()).to_lowercase();
+            let value = attribute.unescape_value().map_err(invalid_data)?;
+            Ok((name, value.into_owned()))
+        })
+        .collect()
+}
+
+/// Parses an attribute value.
+fn parse<T: FromStr>(attributes: &BTreeMap<String, String>, name: &str) -> Option<T> {
+    attributes.get(name).and_then(|value| value.parse().ok())
+}
+
+fn invalid_data(err: impl Into<Box<dyn Error + Send + Sync>>) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"
+        <OverlayData>
+            <MarkerCategory name="Tactics" DisplayName="Tactics" iconFile="data/tactics.png">
+                <MarkerCategory name="Mechanics" DisplayName="Mechanics" defaulttoggle="0" />
+            </MarkerCategory>
+            <POIs>
+                <POI MapID="15" xpos="1.5" ypos="2" zpos="-3" type="tactics.mechanics" GUID="abc" />
+                <POI MapID="50" xpos="0" ypos="0" zpos="0" type="Tactics" iconFile="data/other.png" />
+                <Trail type="tactics" trailData="data/route.trl" texture="data/trail.png" />
+            </POIs>
+        </OverlayData>
+    "#;
+
+    #[test]
+    fn parse_pack() {
+        let mut pack = MarkerPack::new("Test", "");
+        pack.parse_xml(XML).unwrap();
+
+        let mechanics = pack.category("Tactics.Mechanics").unwrap();
+        assert_eq!(mechanics.display_name, "Mechanics");
+        assert_eq!(pack.default_disabled(), ["tactics.mechanics"]);
+
+        let poi = pack.pois_on_map(15).next().unwrap();
+        assert_eq!(poi.position, Vec3::new(1.5, 2.0, -3.0));
+        assert_eq!(pack.icon_file(poi), Some("data/tactics.png"));
+        assert_eq!(pack.icon_file(&pack.pois[1]), Some("data/other.png"));
+        assert_eq!(pack.trails[0].file, "data/route.trl");
+    }
+}
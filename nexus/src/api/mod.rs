@@ -1,26 +1,56 @@
 pub mod alert;
+pub mod coords;
 pub mod data_link;
 pub mod event;
 pub mod font;
+pub mod game;
 pub mod gamebind;
 pub mod gui;
 pub mod hook;
 pub mod keybind;
+pub mod keybind_mirror;
 pub mod localization;
 pub mod log;
 pub mod paths;
 pub mod quick_access;
 pub mod texture;
+pub mod trail;
+pub mod tyria_time;
 pub mod updater;
 pub mod v2;
 pub mod v3;
 pub mod v4;
 pub mod v6;
+pub mod vec3;
 pub mod wnd_proc;
 
+#[cfg(feature = "arc")]
+pub mod account;
+
+#[cfg(feature = "extras")]
+pub mod chat;
+
+#[cfg(feature = "rtapi")]
+pub mod context;
+
+#[cfg(feature = "markers")]
+pub mod markers;
+
+#[cfg(feature = "rtapi")]
+pub mod player_trail;
+
+#[cfg(feature = "rtapi")]
+pub mod projection;
+
+#[cfg(feature = "json")]
+pub mod recording;
+
 #[cfg(feature = "rtapi")]
 pub mod rtapi;
 
+#[cfg(feature = "rtapi")]
+pub mod squad_markers;
+
 // export current supported version
 pub use v6::*;
 
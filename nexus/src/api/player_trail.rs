@@ -0,0 +1,617 @@
+//! Player movement trail recording & replay.
+//!
+//! The player position is sampled once per frame into a bounded ring buffer.
+//! Positions are read from the RealTime API, falling back to the Mumble link with the `"mumble"` feature.
+//! Recorded positions are stored in meters, matching the TacO trail format.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::player_trail::{save_recorded_trails, subscribe_trail_recorder, with_trail_recorder};
+//!
+//! subscribe_trail_recorder().revert_on_unload();
+//!
+//! // later
+//! let stats = with_trail_recorder(|recorder| recorder.stats());
+//! let files = save_recorded_trails("MyAddon");
+//! ```
+
+use crate::{
+    data_link::read_nexus_link,
+    gui::{register_render, RenderType},
+    imgui::Ui,
+    paths::get_addon_dir,
+    projection::Projection,
+    render,
+    revertible::Revertible,
+    rtapi::RealTimeApi,
+    trail::TrailData,
+    vec3::{Units, Vec3},
+};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Shared trail recorder.
+static RECORDER: Mutex<TrailRecorder> = Mutex::new(TrailRecorder::new(RecorderConfig::new()));
+
+/// Shared trail replay.
+static REPLAY: Mutex<Option<TrailReplay>> = Mutex::new(None);
+
+/// Sampling of the player position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sampling {
+    /// Record when the player moved at least the distance in meters.
+    Distance(f32),
+
+    /// Record when at least the interval in seconds passed.
+    Interval(f64),
+}
+
+/// Trail recorder configuration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecorderConfig {
+    /// Sampling of the player position.
+    pub sampling: Sampling,
+
+    /// Maximum number of recorded samples.
+    ///
+    /// The oldest samples are discarded when full.
+    pub capacity: usize,
+
+    /// Distance between samples in meters to treat as teleport, starting a new segment.
+    pub teleport_distance: Option<f32>,
+
+    /// Units of the RealTime API character position.
+    pub position_units: Units,
+}
+
+impl RecorderConfig {
+    /// Creates the default configuration.
+    pub const fn new() -> Self {
+        Self {
+            sampling: Sampling::Distance(1.0),
+            capacity: 10_000,
+            teleport_distance: Some(100.0),
+            position_units: Units::Inches,
+        }
+    }
+}
+
+impl Default for RecorderConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recorded player position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrailSample {
+    /// Map id of the sample.
+    pub map_id: u32,
+
+    /// Player position in meters.
+    pub position: Vec3,
+
+    /// Time of the sample in seconds since the Unix epoch.
+    pub time: f64,
+
+    /// Whether the sample starts a new segment after a map change or teleport.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_break: bool,
+}
+
+/// Movement statistics of a recorded trail.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrailStats {
+    /// Distance travelled in meters, excluding teleports & map changes.
+    pub distance: f32,
+
+    /// Time spent moving in seconds, excluding teleports & map changes.
+    pub duration: f64,
+
+    /// Average speed in meters per second.
+    pub average_speed: f32,
+
+    /// Maximum speed between two samples in meters per second.
+    pub max_speed: f32,
+
+    /// Speed between the last two samples in meters per second.
+    pub current_speed: f32,
+}
+
+impl TrailStats {
+    /// Calculates the statistics of consecutive samples.
+    pub fn from_samples<'a>(samples: impl IntoIterator<Item = &'a TrailSample>) -> Self {
+        let mut stats = Self::default();
+        let mut previous: Option<&TrailSample> = None;
+        for sample in samples {
+            if let Some(previous) = previous.filter(|_| !sample.is_break) {
+                let distance = previous.position.distance(sample.position);
+                let duration = sample.time - previous.time;
+                stats.distance += distance;
+                stats.duration += duration;
+                stats.current_speed = if duration > 0.0 {
+                    (distance as f64 / duration) as f32
+                } else {
+                    0.0
+                };
+                stats.max_speed = stats.max_speed.max(stats.current_speed);
+            } else {
+                stats.current_speed = 0.0;
+            }
+            previous = Some(sample);
+        }
+        if stats.duration > 0.0 {
+            stats.average_speed = (stats.distance as f64 / stats.duration) as f32;
+        }
+        stats
+    }
+}
+
+/// Records player positions into a ring buffer.
+#[derive(Debug, Clone)]
+pub struct TrailRecorder {
+    config: RecorderConfig,
+    samples: VecDeque<TrailSample>,
+}
+
+impl TrailRecorder {
+    /// Creates a new empty recorder.
+    #[inline]
+    pub const fn new(config: RecorderConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Returns the recorder configuration.
+    #[inline]
+    pub fn config(&self) -> &RecorderConfig {
+        &self.config
+    }
+
+    /// Sets the recorder configuration.
+    ///
+    /// Excess samples are discarded if the capacity shrinks.
+    pub fn set_config(&mut self, config: RecorderConfig) {
+        self.config = config;
+        self.truncate(self.config.capacity);
+    }
+
+    /// Records a position in meters at the given time in seconds.
+    ///
+    /// Returns `true` if the position was sampled.
+    pub fn record(&mut self, map_id: u32, position: Vec3, time: f64) -> bool {
+        if self.config.capacity == 0 {
+            return false;
+        }
+        let is_break = match self.samples.back() {
+            Some(last) if last.map_id == map_id => {
+                let distance = last.position.distance(position);
+                let due = match self.config.sampling {
+                    Sampling::Distance(min) => distance >= min,
+                    Sampling::Interval(interval) => time - last.time >= interval,
+                };
+                if !due {
+                    return false;
+                }
+                self.config
+                    .teleport_distance
+                    .is_some_and(|max| distance > max)
+            }
+            _ => true,
+        };
+        self.truncate(self.config.capacity - 1);
+        self.samples.push_back(TrailSample {
+            map_id,
+            position,
+            time,
+            is_break,
+        });
+        true
+    }
+
+    fn truncate(&mut self, len: usize) {
+        while self.samples.len() > len {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Returns an iterator over the recorded samples, oldest first.
+    #[inline]
+    pub fn samples(&self) -> impl Iterator<Item = &TrailSample> {
+        self.samples.iter()
+    }
+
+    /// Returns the most recent sample.
+    #[inline]
+    pub fn last(&self) -> Option<&TrailSample> {
+        self.samples.back()
+    }
+
+    /// Returns the number of recorded samples.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Checks whether no samples are recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Removes all samples.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.samples.clear()
+    }
+
+    /// Returns the ids of all maps with recorded samples.
+    #[inline]
+    pub fn maps(&self) -> BTreeSet<u32> {
+        self.samples.iter().map(|sample| sample.map_id).collect()
+    }
+
+    /// Returns the movement statistics of the recorded samples.
+    #[inline]
+    pub fn stats(&self) -> TrailStats {
+        TrailStats::from_samples(&self.samples)
+    }
+
+    /// Converts the samples on a map to [`TrailData`].
+    #[inline]
+    pub fn to_trail_data(&self, map_id: u32) -> TrailData {
+        to_trail_data(&self.samples, map_id)
+    }
+}
+
+impl Default for TrailRecorder {
+    #[inline]
+    fn default() -> Self {
+        Self::new(RecorderConfig::new())
+    }
+}
+
+/// Converts the samples on a map to [`TrailData`].
+///
+/// Segments are separated by points at the origin.
+pub fn to_trail_data<'a>(
+    samples: impl IntoIterator<Item = &'a TrailSample>,
+    map_id: u32,
+) -> TrailData {
+    let mut data = TrailData::new(map_id);
+    let mut previous_map = None;
+    for sample in samples {
+        if sample.map_id == map_id {
+            let is_new_segment = sample.is_break || previous_map != Some(map_id);
+            if is_new_segment && !data.points.is_empty() {
+                data.points.push(Vec3::ZERO);
+            }
+            data.points.push(sample.position);
+        }
+        previous_map = Some(sample.map_id);
+    }
+    data
+}
+
+/// Saves samples as JSON.
+#[cfg(feature = "json")]
+pub fn save_json<'a>(
+    path: impl AsRef<std::path::Path>,
+    samples: impl IntoIterator<Item = &'a TrailSample>,
+) -> io::Result<()> {
+    let samples = samples.into_iter().collect::<Vec<_>>();
+    let json = serde_json::to_string(&samples)
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    fs::write(path, json)
+}
+
+/// Loads samples from JSON.
+#[cfg(feature = "json")]
+pub fn load_json(path: impl AsRef<std::path::Path>) -> io::Result<Vec<TrailSample>> {
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+/// Returns the `trails` directory of the addon.
+#[inline]
+pub fn trails_dir(addon: impl AsRef<str>) -> Option<PathBuf> {
+    get_addon_dir(addon).map(|dir| dir.join("trails"))
+}
+
+/// Saves the trails recorded by the shared [`TrailRecorder`] under the directory of the given addon.
+///
+/// Writes a `.trl` file per map to `<addon>/trails/<timestamp>_<map id>.trl`.
+/// Returns the paths of the written files.
+pub fn save_recorded_trails(addon: impl AsRef<str>) -> io::Result<Vec<PathBuf>> {
+    let dir = trails_dir(addon).ok_or_else(addon_dir_unavailable)?;
+    fs::create_dir_all(&dir)?;
+    let timestamp = now() as u64;
+    with_trail_recorder(|recorder| {
+        recorder
+            .maps()
+            .into_iter()
+            .map(|map_id| {
+                let path = dir.join(format!("{timestamp}_{map_id}.trl"));
+                recorder.to_trail_data(map_id).save(&path)?;
+                Ok(path)
+            })
+            .collect()
+    })
+}
+
+/// Saves the samples recorded by the shared [`TrailRecorder`] as JSON under the directory of the given addon.
+///
+/// Writes to `<addon>/trails/<timestamp>.json` and returns the path of the written file.
+#[cfg(feature = "json")]
+pub fn save_recording_json(addon: impl AsRef<str>) -> io::Result<PathBuf> {
+    let dir = trails_dir(addon).ok_or_else(addon_dir_unavailable)?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", now() as u64));
+    with_trail_recorder(|recorder| save_json(&path, recorder.samples()))?;
+    Ok(path)
+}
+
+fn addon_dir_unavailable() -> io::Error {
+    io::Error::new(ErrorKind::NotFound, "addon directory unavailable")
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// Reads the current map id & player position in meters.
+fn current_position(units: Units) -> Option<(u32, Vec3)> {
+    if let Some(snapshot) = RealTimeApi::get().and_then(|rtapi| rtapi.snapshot()) {
+        let position = units.position_to_meters(snapshot.player.position());
+        return Some((snapshot.world.map_id, position));
+    }
+
+    #[cfg(feature = "mumble")]
+    if let Some(link) = crate::data_link::read_mumble_link() {
+        use crate::data_link::mumble::MumbleLinkExt;
+        return Some((link.context.map_id, link.avatar_position()));
+    }
+
+    None
+}
+
+/// Registers the shared [`TrailRecorder`] to be polled every frame.
+///
+/// Returns a [`Revertible`] to stop recording.
+pub fn subscribe_trail_recorder() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    extern "C-unwind" fn poll_trail_recorder() {
+        if !read_nexus_link().is_some_and(|link| link.is_gameplay) {
+            return;
+        }
+        with_trail_recorder(|recorder| {
+            if let Some((map_id, position)) = current_position(recorder.config.position_units) {
+                recorder.record(map_id, position, now());
+            }
+        });
+    }
+
+    register_render(RenderType::PreRender, poll_trail_recorder)
+}
+
+/// Accesses the shared [`TrailRecorder`].
+#[inline]
+pub fn with_trail_recorder<R>(body: impl FnOnce(&mut TrailRecorder) -> R) -> R {
+    body(&mut RECORDER.lock().unwrap())
+}
+
+/// Replay of recorded samples as overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrailReplay {
+    /// Replayed samples.
+    pub samples: Vec<TrailSample>,
+
+    /// Playback speed multiplier.
+    pub speed: f64,
+
+    /// Whether to restart the replay at the end.
+    pub looping: bool,
+
+    /// Color of the drawn trail.
+    pub color: [f32; 4],
+
+    /// Thickness of the drawn trail in pixels, multiplied by the UI scaling.
+    pub thickness: f32,
+
+    /// Start time in seconds since the Unix epoch, set on first draw.
+    pub start: Option<f64>,
+}
+
+impl TrailReplay {
+    /// Creates a new replay of the given samples.
+    #[inline]
+    pub fn new(samples: impl Into<Vec<TrailSample>>) -> Self {
+        Self {
+            samples: samples.into(),
+            speed: 1.0,
+            looping: true,
+            color: [1.0, 0.6, 0.2, 0.8],
+            thickness: 3.0,
+            start: None,
+        }
+    }
+
+    /// Returns the recorded duration in seconds.
+    #[inline]
+    pub fn duration(&self) -> f64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the replayed map id & position at the given offset in recorded seconds.
+    ///
+    /// Positions between samples of the same segment are interpolated.
+    pub fn position_at(&self, offset: f64) -> Option<(u32, Vec3)> {
+        let first = self.samples.first()?;
+        let time = first.time + offset;
+        let index = self
+            .samples
+            .partition_point(|sample| sample.time < time)
+            .min(self.samples.len() - 1);
+        let next = &self.samples[index];
+        match index.checked_sub(1).map(|index| &self.samples[index]) {
+            Some(previous) if !next.is_break && next.time > previous.time => {
+                let t = ((time - previous.time) / (next.time - previous.time)).clamp(0.0, 1.0);
+                let position = previous.position + (next.position - previous.position) * t as f32;
+                Some((next.map_id, position))
+            }
+            _ => Some((next.map_id, next.position)),
+        }
+    }
+
+    /// Returns the replay offset in recorded seconds at the given time.
+    pub fn offset(&self, now: f64) -> f64 {
+        let elapsed = self.start.map_or(0.0, |start| (now - start) * self.speed);
+        let duration = self.duration();
+        if self.looping && duration > 0.0 {
+            elapsed % duration
+        } else {
+            elapsed.min(duration)
+        }
+    }
+}
+
+/// Starts a replay overlay, replacing the current replay.
+///
+/// Returns a [`Revertible`] to stop the replay.
+pub fn start_replay(replay: TrailReplay) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    *REPLAY.lock().unwrap() = Some(replay);
+    let render = register_render(RenderType::Render, render!(render_replay)).into_inner();
+    let revert = move || {
+        render();
+        *REPLAY.lock().unwrap() = None;
+    };
+    revert.into()
+}
+
+/// Draws the replayed trail & the current replay position.
+///
+/// Can be used directly from a custom render callback instead of [`start_replay`].
+pub fn draw_replay(ui: &Ui, replay: &mut TrailReplay) {
+    let Some(projection) = Projection::current_or_mumble() else {
+        return;
+    };
+    let Some((map_id, _)) = current_position(Units::Meters) else {
+        return;
+    };
+
+    let now = now();
+    let start = *replay.start.get_or_insert(now);
+    let offset = replay.offset(now);
+    let first = replay.samples.first().map_or(start, |sample| sample.time);
+    let replayed = replay
+        .samples
+        .iter()
+        .take_while(|sample| sample.time - first <= offset);
+    draw_trail(
+        ui,
+        &projection,
+        replayed,
+        map_id,
+        replay.color,
+        replay.thickness,
+    );
+
+    if let Some((replay_map, position)) = replay.position_at(offset) {
+        if replay_map == map_id {
+            if let Some(pos) = projection.project(position, Units::Meters) {
+                ui.get_background_draw_list()
+                    .add_circle(pos, 6.0 * projection.scaling, replay.color)
+                    .filled(true)
+                    .build();
+            }
+        }
+    }
+}
+
+/// Draws the segments of samples on the given map.
+pub fn draw_trail<'a>(
+    ui: &Ui,
+    projection: &Projection,
+    samples: impl IntoIterator<Item = &'a TrailSample>,
+    map_id: u32,
+    color: [f32; 4],
+    thickness: f32,
+) {
+    let draw_list = ui.get_background_draw_list();
+    let mut previous: Option<&TrailSample> = None;
+    for sample in samples {
+        if let Some(previous) = previous.filter(|previous| {
+            !sample.is_break && previous.map_id == map_id && sample.map_id == map_id
+        }) {
+            let start = projection.project_unclipped(previous.position, Units::Meters);
+            let end = projection.project_unclipped(sample.position, Units::Meters);
+            if let (Some(start), Some(end)) = (start, end) {
+                draw_list
+                    .add_line(start, end, color)
+                    .thickness(thickness * projection.scaling)
+                    .build();
+            }
+        }
+        previous = Some(sample);
+    }
+}
+
+fn render_replay(ui: &Ui) {
+    if let Some(replay) = REPLAY.lock().unwrap().as_mut() {
+        draw_replay(ui, replay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_stats() {
+        let mut recorder = TrailRecorder::new(RecorderConfig {
+            capacity: 4,
+            ..RecorderConfig::new()
+        });
+        assert!(recorder.record(15, Vec3::new(0.0, 0.0, 0.0), 0.0));
+        assert!(!recorder.record(15, Vec3::new(0.5, 0.0, 0.0), 0.5));
+        assert!(recorder.record(15, Vec3::new(3.0, 0.0, 4.0), 1.0));
+        assert!(recorder.record(50, Vec3::new(1.0, 1.0, 1.0), 2.0));
+        assert!(recorder.record(15, Vec3::new(3.0, 0.0, 4.0), 3.0));
+        assert!(recorder.record(15, Vec3::new(6.0, 0.0, 8.0), 5.0));
+        assert_eq!(recorder.len(), 4);
+
+        let stats = recorder.stats();
+        assert_eq!(stats.distance, 5.0);
+        assert_eq!(stats.duration, 2.0);
+        assert_eq!(stats.current_speed, 2.5);
+
+        let data = recorder.to_trail_data(15);
+        assert_eq!(data.points.len(), 4);
+        assert_eq!(data.points[1], Vec3::ZERO);
+
+        let replay = TrailReplay::new(recorder.samples().copied().collect::<Vec<_>>());
+        assert_eq!(
+            replay.position_at(3.0),
+            Some((15, Vec3::new(4.5, 0.0, 6.0)))
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Projection of world coordinates to screen coordinates.
+//!
+//! Builds view & projection matrices from the RealTime API [`CameraData`] and the screen size of the [`NexusLink`].
+//! The game uses a left-handed coordinate system with the Y axis pointing up.
+//! Screen coordinates are in pixels with the origin in the top left corner, as used by imgui.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{projection::{Projection, Units}, rtapi::RealTimeApi};
+//!
+//! if let Some(projection) = RealTimeApi::get().and_then(|rtapi| Projection::current(&rtapi)) {
+//!     if let Some(screen) = projection.project([0.0, 0.0, 0.0], Units::Inches) {
+//!         // draw at screen position
+//!     }
+//! }
+//! ```
+
+use crate::{
+    data_link::{read_nexus_link, NexusLink},
+    rtapi::{CameraData, RealTimeApi},
+    vec3::Vec3,
+};
+
+pub use crate::vec3::{Units, INCHES_PER_METER};
+
+/// Distance of the near clipping plane in meters.
+pub const NEAR: f32 = 0.1;
+
+/// Distance of the far clipping plane in meters.
+pub const FAR: f32 = 10_000.0;
+
+/// 4x4 matrix in row-major order, used with row vectors.
+pub type Matrix = [[f32; 4]; 4];
+
+/// Returns the current camera.
+///
+/// Uses the RealTime API if available, otherwise the Mumble link with the `"mumble"` feature.
+/// The Mumble link does not contain the field of view, it is taken from the [Mumble identity](crate::event::current_mumble_identity).
+pub fn current_camera() -> Option<CameraData> {
+    if let Some(camera) = RealTimeApi::get().and_then(|rtapi| rtapi.read_camera()) {
+        return Some(camera);
+    }
+
+    #[cfg(feature = "mumble")]
+    if let Some(link) = crate::data_link::read_mumble_link() {
+        use crate::{context::DEFAULT_FOV, data_link::mumble::MumbleLinkExt};
+
+        let fov =
+            crate::event::current_mumble_identity().map_or(DEFAULT_FOV, |identity| identity.fov);
+        return Some(CameraData {
+            camera_position: link.camera_position().into(),
+            camera_facing: link.camera_front().into(),
+            camera_fov: fov,
+            is_action_camera: false,
+        });
+    }
+
+    None
+}
+
+/// Camera projection for the current frame.
+///
+/// The view is built from the reported camera position & facing only.
+/// Changes of the view origin by the action camera are not applied, so projections may be offset while it is enabled.
+/// [`is_action_camera`](Self::is_action_camera) is only used for the [`crosshair`](Self::crosshair).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    /// View matrix.
+    pub view: Matrix,
+
+    /// Projection matrix.
+    pub projection: Matrix,
+
+    /// Combined view & projection matrix.
+    pub view_projection: Matrix,
+
+    /// Camera position in meters.
+    pub camera_position: Vec3,
+
+    /// Screen width in pixels.
+    pub width: f32,
+
+    /// Screen height in pixels.
+    pub height: f32,
+
+    /// UI scaling.
+    pub scaling: f32,
+
+    /// Whether action camera is enabled.
+    ///
+    /// Not applied to projections.
+    pub is_action_camera: bool,
+}
+
+impl Projection {
+    /// Creates a projection from the given camera & screen data.
+    ///
+    /// The camera position is expected in [`Units::Meters`].
+    pub fn new(camera: &CameraData, width: f32, height: f32, scaling: f32) -> Self {
+        let view = look_to(camera.position(), camera.facing());
+        let projection = perspective(camera.camera_fov, width / height.max(1.0));
+        Self {
+            view_projection: multiply(&view, &projection),
+            view,
+            projection,
+            camera_position: camera.position(),
+            width,
+            height,
+            scaling,
+            is_action_camera: camera.is_action_camera,
+        }
+    }
+
+    /// Creates a projection from the given camera & [`NexusLink`].
+    #[inline]
+    pub fn from_link(camera: &CameraData, link: &NexusLink) -> Self {
+        Self::new(camera, link.width as f32, link.height as f32, link.scaling)
+    }
+
+    /// Creates a projection from the current RealTime API camera & [`NexusLink`].
+    #[inline]
+    pub fn current(rtapi: &RealTimeApi) -> Option<Self> {
+        let camera = rtapi.read_camera()?;
+        let link = read_nexus_link()?;
+        Some(Self::from_link(&camera, &link))
+    }
+
+    /// Creates a projection from the [current camera](current_camera) & [`NexusLink`].
+    ///
+    /// Falls back to the Mumble link without the RealTime API, see [`current_camera`].
+    #[inline]
+    pub fn current_or_mumble() -> Option<Self> {
+        let camera = current_camera()?;
+        let link = read_nexus_link()?;
+        Some(Self::from_link(&camera, &link))
+    }
+
+    /// Transforms a world position into clip space.
+    #[inline]
+    pub fn to_clip(&self, position: impl Into<Vec3>, units: Units) -> [f32; 4] {
+        let Vec3 { x, y, z } = units.position_to_meters(position);
+        transform(&self.view_projection, [x, y, z, 1.0])
+    }
+
+    /// Projects a world position to screen coordinates.
+    ///
+    /// Returns [`None`] if the position is behind the camera, outside the clipping planes or outside the screen.
+    pub fn project(&self, position: impl Into<Vec3>, units: Units) -> Option<[f32; 2]> {
+        let [x, y, z, w] = self.to_clip(position, units);
+        if w < NEAR {
+            return None;
+        }
+        let (x, y, z) = (x / w, y / w, z / w);
+        let visible =
+            (-1.0..=1.0).contains(&x) && (-1.0..=1.0).contains(&y) && (0.0..=1.0).contains(&z);
+        visible.then(|| self.to_screen(x, y))
+    }
+
+    /// Projects a world position to screen coordinates without culling points outside of the screen.
+    ///
+    /// Returns [`None`] if the position is behind the camera.
+    /// Useful for clamping indicators to the screen edge.
+    pub fn project_unclipped(&self, position: impl Into<Vec3>, units: Units) -> Option<[f32; 2]> {
+        let [x, y, _, w] = self.to_clip(position, units);
+        (w >= NEAR).then(|| self.to_screen(x / w, y / w))
+    }
+
+    #[inline]
+    fn to_screen(&self, x: f32, y: f32) -> [f32; 2] {
+        [(x + 1.0) * 0.5 * self.width, (1.0 - y) * 0.5 * self.height]
+    }
+
+    /// Returns the distance of a world position to the camera in the given units.
+    #[inline]
+    pub fn camera_distance(&self, position: impl Into<Vec3>, units: Units) -> f32 {
+        units.meters_to(
+            self.camera_position
+                .distance(units.position_to_meters(position)),
+        )
+    }
+
+    /// Returns the screen center, which is the aim point with action camera enabled.
+    #[inline]
+    pub fn screen_center(&self) -> [f32; 2] {
+        [self.width * 0.5, self.height * 0.5]
+    }
+
+    /// Returns the aim point of the action camera.
+    ///
+    /// Returns [`None`] if action camera is disabled.
+    #[inline]
+    pub fn crosshair(&self) -> Option<[f32; 2]> {
+        self.is_action_camera.then(|| self.screen_center())
+    }
+}
+
+/// Builds a left-handed view matrix looking from the position in the given direction.
+pub fn look_to(position: Vec3, direction: Vec3) -> Matrix {
+    let forward = direction.normalize();
+    let up = if forward.cross(Vec3::UP) == Vec3::ZERO {
+        // looking straight up or down
+        Vec3::new(0.0, 0.0, 1.0)
+    } else {
+        Vec3::UP
+    };
+    let right = up.cross(forward).normalize();
+    let up = forward.cross(right);
+    [
+        [right.x, up.x, forward.x, 0.0],
+        [right.y, up.y, forward.y, 0.0],
+        [right.z, up.z, forward.z, 0.0],
+        [
+            -right.dot(position),
+            -up.dot(position),
+            -forward.dot(position),
+            1.0,
+        ],
+    ]
+}
+
+/// Builds a left-handed perspective projection matrix for the vertical field of view in radians.
+///
+/// Depth is mapped to `0..=1` between [`NEAR`] & [`FAR`].
+pub fn perspective(fov: f32, aspect: f32) -> Matrix {
+    let y_scale = 1.0 / (fov * 0.5).tan();
+    let x_scale = y_scale / aspect;
+    let depth = FAR / (FAR - NEAR);
+    [
+        [x_scale, 0.0, 0.0, 0.0],
+        [0.0, y_scale, 0.0, 0.0],
+        [0.0, 0.0, depth, 1.0],
+        [0.0, 0.0, -NEAR * depth, 0.0],
+    ]
+}
+
+/// Multiplies two matrices.
+pub fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (row, a) in result.iter_mut().zip(a) {
+        for (col, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|i| a[i] * b[i][col]).sum();
+        }
+    }
+    result
+}
+
+/// Transforms a row vector by a matrix.
+pub fn transform(matrix: &Matrix, vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (col, value) in result.iter_mut().enumerate() {
+        *value = (0..4).map(|i| vector[i] * matrix[i][col]).sum();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn projection() -> Projection {
+        let camera = CameraData {
+            camera_position: [0.0, 0.0, 0.0],
+            camera_facing: [0.0, 0.0, 1.0],
+            camera_fov: std::f32::consts::FRAC_PI_2,
+            is_action_camera: false,
+        };
+        Projection::new(&camera, 1920.0, 1080.0, 1.0)
+    }
+
+    #[test]
+    fn project() {
+        let projection = projection();
+        let center = projection.project([0.0, 0.0, 10.0], Units::Meters).unwrap();
+        assert!((center[0] - 960.0).abs() < 0.01 && (center[1] - 540.0).abs() < 0.01);
+
+        // above & right of center
+        let [x, y] = projection.project([1.0, 1.0, 10.0], Units::Meters).unwrap();
+        assert!(x > 960.0 && y < 540.0);
+
+        // behind camera & outside of screen
+        assert_eq!(projection.project([0.0, 0.0, -10.0], Units::Meters), None);
+        assert_eq!(projection.project([100.0, 0.0, 10.0], Units::Meters), None);
+
+        let inches = projection
+            .project([0.0, 0.0, 10.0 * INCHES_PER_METER], Units::Inches)
+            .unwrap();
+        assert!((inches[0] - center[0]).abs() < 0.01);
+    }
+}
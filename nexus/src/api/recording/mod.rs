@@ -0,0 +1,651 @@
+//! Recording & replaying of shared game state.
+//!
+//! A [`Recorder`] writes shared data link resources & event payloads with timestamps as JSON lines.
+//! Resources are only written when their contents changed since the last write.
+//! By default the [`NexusLink`], the Mumble link with the `"mumble"` feature & the RealTime API data with the `"rtapi"` feature are recorded.
+//!
+//! A [`Replayer`] feeds a recording back into a [`ReplayHost`].
+//! [`NexusHost`] writes to the data link & raises events via Nexus.
+//! [`TestHost`] does the same with an in-memory Nexus API from [`test_api`] for tests outside of the game.
+//! Addon code reading the data link or subscribing to events runs unchanged against a replayed recording.
+//!
+//! Resources & event payloads are copied as raw bytes.
+//! Only [`PlainData`] event payloads can be recorded.
+//! Pointers contained in them, like the fonts of the [`NexusLink`], are not valid when replayed.
+//!
+//! Enable the `"json"` feature for recording support.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::{event::WINDOW_RESIZED, record_event, recording::start_recording};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! start_recording("MyAddon")?.revert_on_unload();
+//! record_event!(WINDOW_RESIZED => ()).revert_on_unload();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Replaying a recording in a test:
+//! ```no_run
+//! use nexus::{data_link::read_nexus_link, recording::{Replayer, TestHost}};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let mut replayer = Replayer::load("tests/recording.jsonl")?;
+//! let mut host = TestHost::new();
+//! replayer.advance_to(5000, &mut host);
+//! let link = read_nexus_link();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    data_link::{get_resource, NexusLink},
+    gui::{register_render, RenderType},
+    paths::get_addon_dir,
+    revertible::Revertible,
+    util::str_to_c,
+    AddonApi, DataLinkApi, EventApi,
+};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write},
+    mem,
+    path::{Path, PathBuf},
+    ptr, slice,
+    sync::Mutex,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub mod test_api;
+
+pub use self::test_api::TestHost;
+
+/// Shared recorder.
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+/// Write error which stopped the shared recorder.
+static ERROR: Mutex<Option<io::Error>> = Mutex::new(None);
+
+/// Recorded entry.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordEntry {
+    /// Contents of a shared resource.
+    Resource {
+        /// Time since the recording start in milliseconds.
+        time: u64,
+
+        /// Data link identifier.
+        identifier: String,
+
+        /// Raw resource bytes.
+        #[serde(with = "hex")]
+        data: Vec<u8>,
+    },
+
+    /// Raised event.
+    Event {
+        /// Time since the recording start in milliseconds.
+        time: u64,
+
+        /// Event identifier.
+        identifier: String,
+
+        /// Raw payload bytes.
+        #[serde(default, with = "hex::option")]
+        data: Option<Vec<u8>>,
+    },
+}
+
+impl RecordEntry {
+    /// Returns the time since the recording start in milliseconds.
+    #[inline]
+    pub fn time(&self) -> u64 {
+        match self {
+            Self::Resource { time, .. } | Self::Event { time, .. } => *time,
+        }
+    }
+
+    /// Returns the identifier of the resource or event.
+    #[inline]
+    pub fn identifier(&self) -> &str {
+        match self {
+            Self::Resource { identifier, .. } | Self::Event { identifier, .. } => identifier,
+        }
+    }
+}
+
+/// Records shared resources & events as JSON lines.
+#[derive(Debug)]
+pub struct Recorder<W: Write = BufWriter<File>> {
+    writer: W,
+    start: Instant,
+    watched: Vec<(String, usize)>,
+    last: BTreeMap<String, Vec<u8>>,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a new recorder writing to the given writer.
+    ///
+    /// No resources are watched initially.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            start: Instant::now(),
+            watched: Vec::new(),
+            last: BTreeMap::new(),
+        }
+    }
+
+    /// Watches a shared resource of the given size to be recorded on [`poll`](Self::poll).
+    #[inline]
+    pub fn watch_resource(&mut self, identifier: impl Into<String>, size: usize) {
+        self.watched.push((identifier.into(), size));
+    }
+
+    /// Watches a shared resource of the given type to be recorded on [`poll`](Self::poll).
+    #[inline]
+    pub fn watch<T>(&mut self, identifier: impl Into<String>) {
+        self.watch_resource(identifier, mem::size_of::<T>())
+    }
+
+    /// Watches the [`NexusLink`], Mumble link & RealTime API data depending on enabled features.
+    pub fn watch_defaults(&mut self) {
+        self.watch::<NexusLink>(NexusLink::LINK);
+
+        #[cfg(feature = "mumble")]
+        self.watch::<crate::data_link::MumbleLink>(crate::data_link::mumble::MUMBLE_LINK);
+
+        #[cfg(feature = "rtapi")]
+        self.watch::<crate::rtapi::data::RealTimeData>(crate::rtapi::data::RealTimeData::LINK);
+    }
+
+    /// Returns the time since the recording start in milliseconds.
+    #[inline]
+    pub fn elapsed(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    /// Reads all watched resources & records changed ones.
+    ///
+    /// Missing resources are skipped.
+    pub fn poll(&mut self) -> io::Result<()> {
+        for index in 0..self.watched.len() {
+            let (identifier, size) = &self.watched[index];
+            let ptr = get_resource::<u8>(identifier);
+            if !ptr.is_null() {
+                let data = unsafe { slice::from_raw_parts(ptr, *size) }.to_vec();
+                let identifier = identifier.clone();
+                self.record_resource(identifier, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the contents of a shared resource if it changed since the last record.
+    pub fn record_resource(
+        &mut self,
+        identifier: impl Into<String>,
+        data: Vec<u8>,
+    ) -> io::Result<()> {
+        let identifier = identifier.into();
+        if self.last.get(&identifier) == Some(&data) {
+            return Ok(());
+        }
+        self.last.insert(identifier.clone(), data.clone());
+        self.write(&RecordEntry::Resource {
+            time: self.elapsed(),
+            identifier,
+            data,
+        })
+    }
+
+    /// Records a raised event.
+    #[inline]
+    pub fn record_event(
+        &mut self,
+        identifier: impl Into<String>,
+        data: Option<Vec<u8>>,
+    ) -> io::Result<()> {
+        self.write(&RecordEntry::Event {
+            time: self.elapsed(),
+            identifier: identifier.into(),
+            data,
+        })
+    }
+
+    /// Writes an entry as JSON line.
+    pub fn write(&mut self, entry: &RecordEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Flushes the underlying writer.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl Recorder {
+    /// Creates a new recorder writing to the given file.
+    #[inline]
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Creates a new recorder writing to `<addon>/recordings/<timestamp>.jsonl`.
+    ///
+    /// Default resources are watched, see [`watch_defaults`](Self::watch_defaults).
+    pub fn for_addon(addon: impl AsRef<str>) -> io::Result<Self> {
+        let dir = recordings_dir(addon)
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "addon directory unavailable"))?;
+        fs::create_dir_all(&dir)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut recorder = Self::create(dir.join(format!("{timestamp}.jsonl")))?;
+        recorder.watch_defaults();
+        Ok(recorder)
+    }
+}
+
+/// Returns the `recordings` directory of the addon.
+#[inline]
+pub fn recordings_dir(addon: impl AsRef<str>) -> Option<PathBuf> {
+    get_addon_dir(addon).map(|dir| dir.join("recordings"))
+}
+
+/// Starts recording to the directory of the given addon using the shared [`Recorder`].
+///
+/// Watched resources are polled every frame.
+/// Recording stops on the first failed write, see [`take_recording_error`].
+/// Returns a [`Revertible`] to stop recording.
+pub fn start_recording(
+    addon: impl AsRef<str>,
+) -> io::Result<Revertible<impl Fn() + Send + Sync + Clone + 'static>> {
+    extern "C-unwind" fn poll_recorder() {
+        with_recorder(|recorder| recorder.poll());
+    }
+
+    *RECORDER.lock().unwrap() = Some(Recorder::for_addon(addon)?);
+    let render = register_render(RenderType::PreRender, poll_recorder).into_inner();
+    let revert = move || {
+        render();
+        with_recorder(|recorder| recorder.flush());
+        RECORDER.lock().unwrap().take();
+    };
+    Ok(revert.into())
+}
+
+/// Performs a write with the shared [`Recorder`], if active.
+///
+/// The recorder is stopped if the write fails.
+fn with_recorder(write: impl FnOnce(&mut Recorder) -> io::Result<()>) {
+    let mut shared = RECORDER.lock().unwrap();
+    let Some(recorder) = shared.as_mut() else {
+        return;
+    };
+    if let Err(err) = write(recorder) {
+        #[cfg(feature = "log")]
+        ::log::warn!("stopped recording after failed write: {err}");
+        *shared = None;
+        *ERROR.lock().unwrap() = Some(err);
+    }
+}
+
+/// Returns the write error which stopped the shared [`Recorder`], if any.
+///
+/// The error is cleared afterwards.
+#[inline]
+pub fn take_recording_error() -> Option<io::Error> {
+    ERROR.lock().unwrap().take()
+}
+
+/// Checks whether the shared [`Recorder`] is active.
+#[inline]
+pub fn is_recording() -> bool {
+    RECORDER.lock().unwrap().is_some()
+}
+
+/// Plain data types without padding bytes, safe to record as raw bytes.
+///
+/// # Safety
+/// All bytes of the type must be initialized, so the type must not contain padding.
+/// Pointers are recorded as addresses, which are not valid when replayed.
+pub unsafe trait PlainData: Copy + 'static {}
+
+macro_rules! impl_plain_data {
+    ( $( $ty:ty ),* $(,)? ) => {
+        $( unsafe impl PlainData for $ty {} )*
+    };
+}
+
+impl_plain_data!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+unsafe impl<T: PlainData, const N: usize> PlainData for [T; N] {}
+
+/// Records an event payload with the shared [`Recorder`], if active.
+///
+/// The payload is copied as raw bytes of the type.
+/// Recording stops on the first failed write, see [`take_recording_error`].
+/// See [`record_event`] for a macro subscribing to an event.
+pub fn record_event_data<T: PlainData>(identifier: &str, data: Option<&T>) {
+    with_recorder(|recorder| {
+        let data = data.map(|data| {
+            let ptr: *const T = data;
+            unsafe { slice::from_raw_parts(ptr.cast::<u8>(), mem::size_of::<T>()) }.to_vec()
+        });
+        recorder.record_event(identifier, data)
+    });
+}
+
+/// Macro to subscribe to an event & record its payloads with the shared [`Recorder`].
+///
+/// The event has to be a constant [`Event`](crate::event::Event).
+/// The payload type has to implement [`PlainData`].
+///
+/// Returns a [`Revertible`] to revert the subscribe.
+///
+/// # Usage
+/// ```no_run
+/// use nexus::{event::WINDOW_RESIZED, record_event};
+///
+/// record_event!(WINDOW_RESIZED => ()).revert_on_unload();
+/// ```
+#[macro_export]
+macro_rules! record_event {
+    ( $event:path => $ty:ty $(,)? ) => {
+        $event.subscribe($crate::event::event_consume!(<$ty> |data| {
+            $crate::recording::record_event_data::<$ty>($event.identifier, data)
+        }))
+    };
+}
+
+pub use record_event;
+
+/// Target of a [`Replayer`].
+pub trait ReplayHost {
+    /// Writes the contents of a shared resource.
+    fn write_resource(&mut self, identifier: &str, data: &[u8]);
+
+    /// Raises an event.
+    fn raise_event(&mut self, identifier: &str, data: Option<&[u8]>);
+}
+
+/// Replay host writing to the Nexus data link & raising events via Nexus.
+///
+/// This overwrites shared resources of other addons & should only be used in test environments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NexusHost;
+
+impl ReplayHost for NexusHost {
+    fn write_resource(&mut self, identifier: &str, data: &[u8]) {
+        let identifier = str_to_c(identifier, "failed to convert data link identifier");
+        let DataLinkApi { share, .. } = AddonApi::get().data_link;
+        let ptr = unsafe { share(identifier.as_ptr(), data.len()) }.cast::<u8>();
+        if !ptr.is_null() {
+            unsafe { ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len()) };
+        }
+    }
+
+    fn raise_event(&mut self, identifier: &str, data: Option<&[u8]>) {
+        let identifier = str_to_c(identifier, "failed to convert event identifier");
+        let EventApi { raise, .. } = AddonApi::get().event;
+        // copy to aligned memory, consumers read the payload as their event type
+        let data = data.map(|data| {
+            let mut blocks = Block::alloc(data.len());
+            unsafe {
+                ptr::copy_nonoverlapping(data.as_ptr(), blocks.as_mut_ptr().cast(), data.len())
+            };
+            blocks
+        });
+        let data = data.as_ref().map_or(ptr::null(), |blocks| blocks.as_ptr());
+        unsafe { raise(identifier.as_ptr(), data.cast()) }
+    }
+}
+
+/// Aligned block of raw memory.
+#[derive(Debug, Clone, Copy)]
+#[repr(C, align(16))]
+struct Block([u8; 16]);
+
+impl Block {
+    /// Allocates zeroed blocks for the given size in bytes.
+    fn alloc(size: usize) -> Box<[Self]> {
+        vec![Self([0; 16]); size.div_ceil(16).max(1)].into_boxed_slice()
+    }
+}
+
+/// Replays recorded entries into a [`ReplayHost`].
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    entries: Vec<RecordEntry>,
+    position: usize,
+}
+
+impl Replayer {
+    /// Creates a new replayer for the given entries.
+    #[inline]
+    pub fn new(entries: Vec<RecordEntry>) -> Self {
+        Self {
+            entries,
+            position: 0,
+        }
+    }
+
+    /// Loads a recording from a JSON lines file.
+    #[inline]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Loads a recording from a reader of JSON lines.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(Self::new(entries))
+    }
+
+    /// Returns the recorded entries.
+    #[inline]
+    pub fn entries(&self) -> &[RecordEntry] {
+        &self.entries
+    }
+
+    /// Returns the duration of the recording in milliseconds.
+    #[inline]
+    pub fn duration(&self) -> u64 {
+        self.entries.last().map_or(0, RecordEntry::time)
+    }
+
+    /// Returns the time of the next entry in milliseconds.
+    #[inline]
+    pub fn next_time(&self) -> Option<u64> {
+        self.entries.get(self.position).map(RecordEntry::time)
+    }
+
+    /// Checks whether all entries were replayed.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.entries.len()
+    }
+
+    /// Replays the next entry.
+    ///
+    /// Returns the replayed entry.
+    pub fn step(&mut self, host: &mut impl ReplayHost) -> Option<&RecordEntry> {
+        let entry = self.entries.get(self.position)?;
+        self.position += 1;
+        match entry {
+            RecordEntry::Resource {
+                identifier, data, ..
+            } => host.write_resource(identifier, data),
+            RecordEntry::Event {
+                identifier, data, ..
+            } => host.raise_event(identifier, data.as_deref()),
+        }
+        Some(entry)
+    }
+
+    /// Replays all entries up to the given time in milliseconds.
+    ///
+    /// Returns the number of replayed entries.
+    pub fn advance_to(&mut self, time: u64, host: &mut impl ReplayHost) -> usize {
+        let mut count = 0;
+        while self.next_time().is_some_and(|next| next <= time) {
+            self.step(host);
+            count += 1;
+        }
+        count
+    }
+
+    /// Replays all remaining entries.
+    #[inline]
+    pub fn run(&mut self, host: &mut impl ReplayHost) -> usize {
+        self.advance_to(u64::MAX, host)
+    }
+
+    /// Restarts the replay from the beginning.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.position = 0;
+    }
+}
+
+/// Hex encoding of raw bytes.
+mod hex {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        decode(&string).ok_or_else(|| D::Error::custom("invalid hex string"))
+    }
+
+    fn encode(data: &[u8]) -> String {
+        data.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn decode(string: &str) -> Option<Vec<u8>> {
+        if string.len() % 2 != 0 {
+            return None;
+        }
+        (0..string.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(string.get(i..i + 2)?, 16).ok())
+            .collect()
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            data: &Option<Vec<u8>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match data {
+                Some(data) => super::serialize(data, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Vec<u8>>, D::Error> {
+            match Option::<String>::deserialize(deserializer)? {
+                Some(string) => super::decode(&string)
+                    .map(Some)
+                    .ok_or_else(|| serde::de::Error::custom("invalid hex string")),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_link::read_nexus_link,
+        event::{event_consume, Event},
+    };
+
+    #[test]
+    fn record_and_replay() {
+        const TEST_EVENT: Event<u32> = unsafe { Event::new("EV_RECORDING_TEST") };
+        static RECEIVED: Mutex<Vec<Option<u32>>> = Mutex::new(Vec::new());
+
+        let mut link = vec![0; mem::size_of::<NexusLink>()];
+        link[0..4].copy_from_slice(&1920u32.to_ne_bytes());
+        link[4..8].copy_from_slice(&1080u32.to_ne_bytes());
+
+        let mut recorder = Recorder::new(Vec::new());
+        recorder
+            .record_resource(NexusLink::LINK, link.clone())
+            .unwrap();
+        recorder.record_resource(NexusLink::LINK, link).unwrap();
+        recorder
+            .record_event(TEST_EVENT.identifier, Some(7u32.to_ne_bytes().to_vec()))
+            .unwrap();
+        recorder.record_event(TEST_EVENT.identifier, None).unwrap();
+
+        let output = recorder.into_inner();
+        let mut replayer = Replayer::from_reader(output.as_slice()).unwrap();
+        assert_eq!(replayer.entries().len(), 3);
+
+        let mut host = TestHost::new();
+        let unsubscribe = TEST_EVENT
+            .subscribe(event_consume!(<u32> |data| {
+                RECEIVED.lock().unwrap().push(data.copied())
+            }))
+            .into_inner();
+
+        replayer.step(&mut host);
+        let link = read_nexus_link().unwrap();
+        assert_eq!((link.width, link.height), (1920, 1080));
+        assert!(RECEIVED.lock().unwrap().is_empty());
+
+        assert_eq!(replayer.run(&mut host), 2);
+        assert!(replayer.is_finished());
+        assert_eq!(*RECEIVED.lock().unwrap(), [Some(7), None]);
+
+        unsubscribe();
+        assert_eq!(test_api::subscriber_count(TEST_EVENT.identifier), 0);
+    }
+}
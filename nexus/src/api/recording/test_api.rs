@@ -0,0 +1,513 @@
+//! In-memory Nexus API for running addon code outside of the game.
+//!
+//! The data link & events are backed by process-wide memory.
+//! All other functions are no-ops returning `null`, `false` or an error.
+//! The DirectX swap chain is a dangling placeholder & must not be used.
+
+use super::{Block, NexusHost, ReplayHost};
+use crate::{
+    event::RawEventConsumeUnknown,
+    font::RawFontReceive,
+    gamebind::GameBind,
+    gui::{RawGuiRender, RenderType},
+    hook::HookStatus,
+    keybind::{Keybind, RawKeybindHandler},
+    log::LogLevel,
+    texture::{RawTextureReceiveCallback, Texture},
+    wnd_proc::RawWndProcCallback,
+    AddonApi, DataLinkApi, EventApi, FontApi, GameBindApi, InputBindsApi, LocalizationApi,
+    MinHookApi, PathApi, QuickAccessApi, RendererApi, TextureApi, UiApi, WndProcApi,
+};
+use imgui::sys::ImFontConfig;
+use std::{
+    collections::BTreeMap,
+    ffi::{c_char, c_void, CStr, CString},
+    ptr::{self, NonNull},
+    sync::{Mutex, OnceLock},
+};
+use windows::{
+    core::Interface,
+    Win32::{
+        Foundation::{HMODULE, HWND, LPARAM, LRESULT, WPARAM},
+        Graphics::Dxgi::IDXGISwapChain,
+    },
+};
+
+/// In-memory API instance.
+static TEST_API: OnceLock<&'static AddonApi> = OnceLock::new();
+
+/// Shared resources & event subscriptions.
+static STATE: Mutex<State> = Mutex::new(State::new());
+
+#[derive(Debug)]
+struct State {
+    /// Resource memory by identifier.
+    ///
+    /// Memory is leaked, so pointers handed out stay valid like with Nexus.
+    resources: BTreeMap<CString, (*mut u8, usize)>,
+
+    /// Event callbacks by identifier.
+    subscriptions: Vec<(CString, RawEventConsumeUnknown)>,
+}
+
+unsafe impl Send for State {}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            resources: BTreeMap::new(),
+            subscriptions: Vec::new(),
+        }
+    }
+}
+
+/// Installs the in-memory API as global [`AddonApi`].
+///
+/// Returns `false` if a different API was already initialized, for example when running inside the game.
+pub fn install() -> bool {
+    let api = *TEST_API.get_or_init(|| &*Box::leak(Box::new(create_api())));
+    crate::globals::init_addon_api(api) || ptr::eq(AddonApi::get(), api)
+}
+
+/// Removes all shared resources & event subscriptions of the in-memory API.
+///
+/// Memory of removed resources stays allocated.
+pub fn reset() {
+    let mut state = STATE.lock().unwrap();
+    state.resources.clear();
+    state.subscriptions.clear();
+}
+
+/// Returns the number of callbacks subscribed to the given event.
+pub fn subscriber_count(identifier: &str) -> usize {
+    STATE
+        .lock()
+        .unwrap()
+        .subscriptions
+        .iter()
+        .filter(|(id, _)| id.as_bytes() == identifier.as_bytes())
+        .count()
+}
+
+/// Replay host using the in-memory API.
+///
+/// Replayed resources are readable via the data link, for example with [`read_nexus_link`](crate::data_link::read_nexus_link).
+/// Replayed events are passed to callbacks subscribed with [`Event::subscribe`](crate::event::Event::subscribe).
+#[derive(Debug, Clone, Copy)]
+pub struct TestHost(NexusHost);
+
+impl TestHost {
+    /// Creates a new host & installs the in-memory API.
+    ///
+    /// Panics if a different API was already initialized.
+    #[inline]
+    pub fn new() -> Self {
+        assert!(install(), "addon api already initialized");
+        Self(NexusHost)
+    }
+}
+
+impl Default for TestHost {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplayHost for TestHost {
+    #[inline]
+    fn write_resource(&mut self, identifier: &str, data: &[u8]) {
+        self.0.write_resource(identifier, data)
+    }
+
+    #[inline]
+    fn raise_event(&mut self, identifier: &str, data: Option<&[u8]>) {
+        self.0.raise_event(identifier, data)
+    }
+}
+
+fn create_api() -> AddonApi {
+    AddonApi {
+        swap_chain: unsafe { IDXGISwapChain::from_raw(NonNull::dangling().as_ptr()) },
+        imgui_context: ptr::null_mut(),
+        imgui_malloc: None,
+        imgui_free: None,
+        renderer: RendererApi {
+            register: render_register,
+            deregister: render_deregister,
+        },
+        request_update,
+        log: log_message,
+        ui: UiApi {
+            send_alert: identifier_noop,
+            register_close_on_escape,
+            deregister_close_on_escape: identifier_noop,
+        },
+        path: PathApi {
+            get_game_dir: no_dir,
+            get_addon_dir,
+            get_common_dir: no_dir,
+        },
+        min_hook: MinHookApi {
+            create: hook_create,
+            remove: hook_generic,
+            enable: hook_generic,
+            disable: hook_generic,
+        },
+        event: EventApi {
+            raise: event_raise,
+            raise_notification: event_raise_notification,
+            raise_targeted: event_raise_targeted,
+            raise_notification_targeted: event_raise_notification_targeted,
+            subscribe: event_subscribe,
+            unsubscribe: event_unsubscribe,
+        },
+        wnd_proc: WndProcApi {
+            register: wnd_proc_generic,
+            deregister: wnd_proc_generic,
+            send_to_game_only: wnd_proc_send,
+        },
+        input_binds: InputBindsApi {
+            invoke: keybind_invoke,
+            register_with_string: keybind_register_with_string,
+            register_with_struct: keybind_register_with_struct,
+            deregister: identifier_noop,
+        },
+        game_bind: GameBindApi {
+            press_async: game_bind_generic,
+            release_async: game_bind_generic,
+            invoke_async: game_bind_invoke_async,
+            press: game_bind_generic,
+            release: game_bind_generic,
+            is_bound: game_bind_is_bound,
+        },
+        data_link: DataLinkApi {
+            get: data_get,
+            share: data_share,
+        },
+        texture: TextureApi {
+            get: texture_get,
+            get_or_create_from_file: texture_from_file,
+            get_or_create_from_resource: texture_from_resource,
+            get_or_create_from_url: texture_from_url,
+            get_or_create_from_memory: texture_from_memory,
+            load_from_file: texture_load_from_file,
+            load_from_resource: texture_load_from_resource,
+            load_from_url: texture_load_from_url,
+            load_from_memory: texture_load_from_memory,
+        },
+        quick_access: QuickAccessApi {
+            add: quick_access_add,
+            remove: identifier_noop,
+            notify: identifier_noop,
+            add_context_menu: quick_access_add_context_menu,
+            remove_context_menu: identifier_noop,
+        },
+        localization: LocalizationApi {
+            translate,
+            translate_to,
+            set: localization_set,
+        },
+        font: FontApi {
+            get: font_generic,
+            release: font_generic,
+            add_from_file: font_from_file,
+            add_from_resource: font_from_resource,
+            add_from_memory: font_from_memory,
+            resize: font_resize,
+        },
+    }
+}
+
+unsafe extern "C-unwind" fn data_get(identifier: *const c_char) -> *const c_void {
+    let identifier = CStr::from_ptr(identifier);
+    STATE
+        .lock()
+        .unwrap()
+        .resources
+        .get(identifier)
+        .map_or(ptr::null(), |(ptr, _)| ptr.cast_const().cast())
+}
+
+unsafe extern "C-unwind" fn data_share(identifier: *const c_char, size: usize) -> *mut c_void {
+    let identifier = CStr::from_ptr(identifier);
+    let mut state = STATE.lock().unwrap();
+    match state.resources.get(identifier) {
+        Some((ptr, capacity)) if *capacity >= size => ptr.cast(),
+        _ => {
+            let ptr = Box::leak(Block::alloc(size)).as_mut_ptr().cast::<u8>();
+            state.resources.insert(identifier.into(), (ptr, size));
+            ptr.cast()
+        }
+    }
+}
+
+unsafe extern "C-unwind" fn event_raise(identifier: *const c_char, data: *const c_void) {
+    let identifier = CStr::from_ptr(identifier);
+    let callbacks: Vec<_> = STATE
+        .lock()
+        .unwrap()
+        .subscriptions
+        .iter()
+        .filter(|(id, _)| id.as_c_str() == identifier)
+        .map(|(_, callback)| *callback)
+        .collect();
+    for callback in callbacks {
+        callback(data);
+    }
+}
+
+unsafe extern "C-unwind" fn event_raise_notification(identifier: *const c_char) {
+    event_raise(identifier, ptr::null())
+}
+
+unsafe extern "C-unwind" fn event_raise_targeted(
+    _signature: i32,
+    identifier: *const c_char,
+    data: *const c_void,
+) {
+    event_raise(identifier, data)
+}
+
+unsafe extern "C-unwind" fn event_raise_notification_targeted(
+    _signature: i32,
+    identifier: *const c_char,
+) {
+    event_raise(identifier, ptr::null())
+}
+
+unsafe extern "C-unwind" fn event_subscribe(
+    identifier: *const c_char,
+    callback: RawEventConsumeUnknown,
+) {
+    let identifier = CStr::from_ptr(identifier).into();
+    STATE
+        .lock()
+        .unwrap()
+        .subscriptions
+        .push((identifier, callback));
+}
+
+unsafe extern "C-unwind" fn event_unsubscribe(
+    identifier: *const c_char,
+    callback: RawEventConsumeUnknown,
+) {
+    let identifier = CStr::from_ptr(identifier);
+    let mut state = STATE.lock().unwrap();
+    if let Some(index) = state
+        .subscriptions
+        .iter()
+        .position(|(id, other)| id.as_c_str() == identifier && *other as usize == callback as usize)
+    {
+        state.subscriptions.remove(index);
+    }
+}
+
+unsafe extern "C-unwind" fn translate(identifier: *const c_char) -> *const c_char {
+    identifier
+}
+
+unsafe extern "C-unwind" fn translate_to(
+    identifier: *const c_char,
+    _language: *const c_char,
+) -> *const c_char {
+    identifier
+}
+
+unsafe extern "C-unwind" fn localization_set(
+    _identifier: *const c_char,
+    _language: *const c_char,
+    _string: *const c_char,
+) {
+}
+
+unsafe extern "C-unwind" fn identifier_noop(_identifier: *const c_char) {}
+
+unsafe extern "C-unwind" fn render_register(_render_type: RenderType, _callback: RawGuiRender) {}
+
+unsafe extern "C-unwind" fn render_deregister(_callback: RawGuiRender) {}
+
+unsafe extern "C-unwind" fn request_update(_signature: i32, _update_url: *const c_char) {}
+
+unsafe extern "C-unwind" fn log_message(
+    _level: LogLevel,
+    _channel: *const c_char,
+    _message: *const c_char,
+) {
+}
+
+unsafe extern "C-unwind" fn register_close_on_escape(
+    _window_name: *const c_char,
+    _is_visible: *mut bool,
+) {
+}
+
+unsafe extern "C-unwind" fn no_dir() -> *const c_char {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn get_addon_dir(_name: *const c_char) -> *const c_char {
+    ptr::null()
+}
+
+unsafe extern "system-unwind" fn hook_create(
+    _target: *const c_void,
+    _detour: *const c_void,
+    _trampoline: *mut *const c_void,
+) -> HookStatus {
+    HookStatus::ErrorNotInitialized
+}
+
+unsafe extern "system-unwind" fn hook_generic(_target: *const c_void) -> HookStatus {
+    HookStatus::ErrorNotInitialized
+}
+
+unsafe extern "C-unwind" fn wnd_proc_generic(_callback: RawWndProcCallback) {}
+
+unsafe extern "C-unwind" fn wnd_proc_send(
+    _h_wnd: HWND,
+    _u_msg: u32,
+    _w_param: WPARAM,
+    _l_param: LPARAM,
+) -> LRESULT {
+    LRESULT(0)
+}
+
+unsafe extern "C-unwind" fn keybind_invoke(_identifier: *const c_char, _is_release: bool) {}
+
+unsafe extern "C-unwind" fn keybind_register_with_string(
+    _identifier: *const c_char,
+    _handler: RawKeybindHandler,
+    _keybind: *const c_char,
+) {
+}
+
+unsafe extern "C-unwind" fn keybind_register_with_struct(
+    _identifier: *const c_char,
+    _handler: RawKeybindHandler,
+    _keybind: Keybind,
+) {
+}
+
+unsafe extern "C-unwind" fn game_bind_generic(_game_bind: GameBind) {}
+
+unsafe extern "C-unwind" fn game_bind_invoke_async(_game_bind: GameBind, _duration: i32) {}
+
+unsafe extern "C-unwind" fn game_bind_is_bound(_game_bind: GameBind) -> bool {
+    false
+}
+
+unsafe extern "C-unwind" fn texture_get(_identifier: *const c_char) -> *const Texture {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn texture_from_file(
+    _identifier: *const c_char,
+    _filename: *const c_char,
+) -> *const Texture {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn texture_from_resource(
+    _identifier: *const c_char,
+    _resource_id: u32,
+    _module: HMODULE,
+) -> *const Texture {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn texture_from_url(
+    _identifier: *const c_char,
+    _remote: *const c_char,
+    _endpoint: *const c_char,
+) -> *const Texture {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn texture_from_memory(
+    _identifier: *const c_char,
+    _data: *const c_void,
+    _size: usize,
+) -> *const Texture {
+    ptr::null()
+}
+
+unsafe extern "C-unwind" fn texture_load_from_file(
+    _identifier: *const c_char,
+    _filename: *const c_char,
+    _callback: RawTextureReceiveCallback,
+) {
+}
+
+unsafe extern "C-unwind" fn texture_load_from_resource(
+    _identifier: *const c_char,
+    _resource_id: u32,
+    _module: HMODULE,
+    _callback: RawTextureReceiveCallback,
+) {
+}
+
+unsafe extern "C-unwind" fn texture_load_from_url(
+    _identifier: *const c_char,
+    _remote: *const c_char,
+    _endpoint: *const c_char,
+    _callback: RawTextureReceiveCallback,
+) {
+}
+
+unsafe extern "C-unwind" fn texture_load_from_memory(
+    _identifier: *const c_char,
+    _data: *const c_void,
+    _size: usize,
+    _callback: RawTextureReceiveCallback,
+) {
+}
+
+unsafe extern "C-unwind" fn quick_access_add(
+    _identifier: *const c_char,
+    _texture_identifier: *const c_char,
+    _texture_hover_identifier: *const c_char,
+    _keybind_identifier: *const c_char,
+    _tooltip_text: *const c_char,
+) {
+}
+
+unsafe extern "C-unwind" fn quick_access_add_context_menu(
+    _identifier: *const c_char,
+    _target_identifier: *const c_char,
+    _callback: RawGuiRender,
+) {
+}
+
+unsafe extern "C-unwind" fn font_generic(_identifier: *const c_char, _callback: RawFontReceive) {}
+
+unsafe extern "C-unwind" fn font_from_file(
+    _identifier: *const c_char,
+    _font_size: f32,
+    _filename: *const c_char,
+    _callback: RawFontReceive,
+    _config: *const ImFontConfig,
+) {
+}
+
+unsafe extern "C-unwind" fn font_from_resource(
+    _identifier: *const c_char,
+    _font_size: f32,
+    _resource_id: u32,
+    _module: HMODULE,
+    _callback: RawFontReceive,
+    _config: *const ImFontConfig,
+) {
+}
+
+unsafe extern "C-unwind" fn font_from_memory(
+    _identifier: *const c_char,
+    _font_size: f32,
+    _data: *const c_void,
+    _size: usize,
+    _callback: RawFontReceive,
+    _config: *const ImFontConfig,
+) {
+}
+
+unsafe extern "C-unwind" fn font_resize(_identifier: *const c_char, _font_size: f32) {}
@@ -1,4 +1,5 @@
 use super::RealTimeData;
+use crate::vec3::Vec3;
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -29,4 +30,16 @@ impl CameraData {
             is_action_camera: (*data).is_action_camera.is_action_camera(),
         }
     }
+
+    /// Returns the camera position as [`Vec3`].
+    #[inline]
+    pub fn position(&self) -> Vec3 {
+        self.camera_position.into()
+    }
+
+    /// Returns the camera facing direction as [`Vec3`].
+    #[inline]
+    pub fn facing(&self) -> Vec3 {
+        self.camera_facing.into()
+    }
 }
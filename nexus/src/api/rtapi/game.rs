@@ -26,6 +26,12 @@ impl GameData {
             language: (*data).language.try_into(),
         }
     }
+
+    /// Checks whether the raw game state is a known [`GameState`].
+    #[inline]
+    pub fn is_valid_state(game_state: u32) -> bool {
+        GameState::try_from(game_state).is_ok()
+    }
 }
 
 #[derive(
@@ -1,4 +1,9 @@
+mod tracker;
+
+pub use self::tracker::*;
+
 use super::RealTimeData;
+use crate::{game::impl_game_ids, vec3::Vec3};
 use bitfields::bitfield;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::ffi::{c_char, CStr};
@@ -28,6 +33,12 @@ impl GroupData {
             group_member_count: (*data).group_member_count,
         }
     }
+
+    /// Returns the squad marker locations as [`Vec3`].
+    #[inline]
+    pub fn squad_marker_positions(&self) -> [Vec3; 8] {
+        self.squad_markers.map(Vec3::from)
+    }
 }
 
 #[derive(
@@ -88,6 +99,11 @@ pub struct GroupMember {
     flags: GroupMemberFlags,
 }
 
+impl_game_ids!(GroupMember {
+    profession,
+    elite_specialization
+});
+
 impl GroupMember {
     /// Converts the member to a [`GroupMemberOwned`].
     #[inline]
@@ -176,7 +192,7 @@ pub struct GroupMemberFlags {
 }
 
 /// Group Member as owned version.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupMemberOwned {
     /// Account name of the group member.
@@ -222,3 +238,8 @@ impl From<&GroupMember> for GroupMemberOwned {
         }
     }
 }
+
+impl_game_ids!(GroupMemberOwned {
+    profession,
+    elite_specialization
+});
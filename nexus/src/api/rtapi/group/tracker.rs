@@ -0,0 +1,421 @@
+//! Group member tracking from RealTime API group events.
+
+use super::{GroupMember, GroupMemberOwned};
+use crate::{
+    callbacks::Callbacks,
+    event::event_consume,
+    game::{Profession, Specialization},
+    revertible::Revertible,
+    rtapi::{
+        event::{RTAPI_GROUP_MEMBER_JOINED, RTAPI_GROUP_MEMBER_LEFT, RTAPI_GROUP_MEMBER_UPDATE},
+        RealTimeApi,
+    },
+};
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Shared group tracker.
+static GROUP_TRACKER: Mutex<GroupTracker> = Mutex::new(GroupTracker::new());
+
+/// Registered change callbacks.
+static CALLBACKS: Callbacks<GroupChange> = Callbacks::new();
+
+/// Change of the tracked group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GroupChange {
+    /// Member joined the group.
+    Joined(GroupMemberOwned),
+
+    /// Member left the group.
+    Left(GroupMemberOwned),
+
+    /// Member moved to a different subgroup.
+    SubgroupChanged {
+        account_name: String,
+        from: u32,
+        to: u32,
+    },
+
+    /// Member switched character.
+    CharacterChanged {
+        account_name: String,
+        from: String,
+        to: String,
+    },
+
+    /// Member changed profession or 3rd specialization.
+    SpecializationChanged {
+        account_name: String,
+        profession: Result<Profession, u32>,
+        specialization: Result<Specialization, u32>,
+    },
+
+    /// Member was promoted to or demoted from commander.
+    CommanderChanged {
+        account_name: String,
+        is_commander: bool,
+    },
+
+    /// Member was promoted to or demoted from lieutenant.
+    LieutenantChanged {
+        account_name: String,
+        is_lieutenant: bool,
+    },
+
+    /// Member entered or left the instance of the player.
+    InstanceChanged {
+        account_name: String,
+        is_in_instance: bool,
+    },
+
+    /// Number of tracked members differs from the RealTime API member count.
+    ///
+    /// The RealTime API only exposes the count, so the members are kept until their events are delivered.
+    CountMismatch { tracked: usize, expected: u32 },
+}
+
+impl GroupChange {
+    /// Returns the account name of the affected member.
+    #[inline]
+    pub fn account_name(&self) -> Option<&str> {
+        match self {
+            Self::Joined(member) | Self::Left(member) => Some(&member.account_name),
+            Self::SubgroupChanged { account_name, .. }
+            | Self::CharacterChanged { account_name, .. }
+            | Self::SpecializationChanged { account_name, .. }
+            | Self::CommanderChanged { account_name, .. }
+            | Self::LieutenantChanged { account_name, .. }
+            | Self::InstanceChanged { account_name, .. } => Some(account_name),
+            Self::CountMismatch { .. } => None,
+        }
+    }
+}
+
+/// Tracks group members by account name.
+#[derive(Debug, Clone, Default)]
+pub struct GroupTracker {
+    members: BTreeMap<String, GroupMemberOwned>,
+}
+
+impl GroupTracker {
+    /// Creates a new empty tracker.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            members: BTreeMap::new(),
+        }
+    }
+
+    /// Processes a joined member.
+    pub fn join(&mut self, member: GroupMemberOwned) -> Vec<GroupChange> {
+        self.update(member)
+    }
+
+    /// Processes a member leaving.
+    pub fn leave(&mut self, account_name: &str) -> Vec<GroupChange> {
+        self.members
+            .remove(account_name)
+            .map(GroupChange::Left)
+            .into_iter()
+            .collect()
+    }
+
+    /// Processes an updated member.
+    ///
+    /// Unknown members are treated as joined.
+    pub fn update(&mut self, member: GroupMemberOwned) -> Vec<GroupChange> {
+        if member.account_name.is_empty() {
+            return Vec::new();
+        }
+        match self.members.get_mut(&member.account_name) {
+            Some(previous) => {
+                let changes = Self::diff(previous, &member);
+                *previous = member;
+                changes
+            }
+            None => {
+                self.members
+                    .insert(member.account_name.clone(), member.clone());
+                vec![GroupChange::Joined(member)]
+            }
+        }
+    }
+
+    fn diff(previous: &GroupMemberOwned, member: &GroupMemberOwned) -> Vec<GroupChange> {
+        let account_name = || member.account_name.clone();
+        let mut changes = Vec::new();
+        if previous.subgroup != member.subgroup {
+            changes.push(GroupChange::SubgroupChanged {
+                account_name: account_name(),
+                from: previous.subgroup,
+                to: member.subgroup,
+            });
+        }
+        if previous.character_name != member.character_name {
+            changes.push(GroupChange::CharacterChanged {
+                account_name: account_name(),
+                from: previous.character_name.clone(),
+                to: member.character_name.clone(),
+            });
+        }
+        if previous.profession != member.profession
+            || previous.elite_specialization != member.elite_specialization
+        {
+            changes.push(GroupChange::SpecializationChanged {
+                account_name: account_name(),
+                profession: member.try_into(),
+                specialization: member.try_into(),
+            });
+        }
+        if previous.is_commander != member.is_commander {
+            changes.push(GroupChange::CommanderChanged {
+                account_name: account_name(),
+                is_commander: member.is_commander,
+            });
+        }
+        if previous.is_lieutenant != member.is_lieutenant {
+            changes.push(GroupChange::LieutenantChanged {
+                account_name: account_name(),
+                is_lieutenant: member.is_lieutenant,
+            });
+        }
+        if previous.is_in_instance != member.is_in_instance {
+            changes.push(GroupChange::InstanceChanged {
+                account_name: account_name(),
+                is_in_instance: member.is_in_instance,
+            });
+        }
+        changes
+    }
+
+    /// Checks the tracked members against the RealTime API group member count.
+    ///
+    /// Without any group members all tracked members are removed.
+    /// Otherwise a differing count only emits [`GroupChange::CountMismatch`],
+    /// as the count may change before the corresponding join or leave event is delivered.
+    pub fn check_count(&mut self, member_count: u32) -> Vec<GroupChange> {
+        let tracked = self.members.len();
+        if member_count == 0 {
+            return std::mem::take(&mut self.members)
+                .into_values()
+                .map(GroupChange::Left)
+                .collect();
+        }
+        if tracked == member_count as usize {
+            return Vec::new();
+        }
+        vec![GroupChange::CountMismatch {
+            tracked,
+            expected: member_count,
+        }]
+    }
+
+    /// Returns the member with the given account name.
+    #[inline]
+    pub fn get(&self, account_name: &str) -> Option<&GroupMemberOwned> {
+        self.members.get(account_name)
+    }
+
+    /// Returns an iterator over all members.
+    #[inline]
+    pub fn members(&self) -> impl Iterator<Item = &GroupMemberOwned> {
+        self.members.values()
+    }
+
+    /// Returns an iterator over the members of a subgroup.
+    #[inline]
+    pub fn subgroup(&self, subgroup: u32) -> impl Iterator<Item = &GroupMemberOwned> {
+        self.members()
+            .filter(move |member| member.subgroup == subgroup)
+    }
+
+    /// Returns the member representing the local player.
+    #[inline]
+    pub fn self_member(&self) -> Option<&GroupMemberOwned> {
+        self.members().find(|member| member.is_self)
+    }
+
+    /// Returns the commander of the squad.
+    #[inline]
+    pub fn commander(&self) -> Option<&GroupMemberOwned> {
+        self.members().find(|member| member.is_commander)
+    }
+
+    /// Returns the number of members.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Checks whether no members are tracked.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Removes all members.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.members.clear()
+    }
+}
+
+/// Subscribes the shared [`GroupTracker`] to RealTime API group events.
+///
+/// After each event the tracker is checked against the current group member count.
+///
+/// Returns a [`Revertible`] to revert the subscribes.
+pub fn subscribe_group_tracker() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    let joined = RTAPI_GROUP_MEMBER_JOINED
+        .subscribe(event_consume!(<GroupMember> |member| {
+            if let Some(member) = member {
+                process(|tracker| tracker.join(member.into()));
+            }
+        }))
+        .into_inner();
+    let left = RTAPI_GROUP_MEMBER_LEFT
+        .subscribe(event_consume!(<GroupMember> |member| {
+            if let Some(member) = member {
+                process(|tracker| tracker.leave(&member.account_name()));
+            }
+        }))
+        .into_inner();
+    let update = RTAPI_GROUP_MEMBER_UPDATE
+        .subscribe(event_consume!(<GroupMember> |member| {
+            if let Some(member) = member {
+                process(|tracker| tracker.update(member.into()));
+            }
+        }))
+        .into_inner();
+    let revert = move || {
+        joined();
+        left();
+        update();
+    };
+    revert.into()
+}
+
+/// Applies a change to the shared tracker, checks its member count & notifies callbacks.
+fn process(body: impl FnOnce(&mut GroupTracker) -> Vec<GroupChange>) {
+    let changes = with_group_tracker(|tracker| {
+        let mut changes = body(tracker);
+        if let Some(group) = RealTimeApi::get().and_then(|rtapi| rtapi.read_group()) {
+            changes.extend(tracker.check_count(group.group_member_count));
+        }
+        changes
+    });
+    for change in &changes {
+        CALLBACKS.invoke(change);
+    }
+}
+
+/// Accesses the shared [`GroupTracker`].
+#[inline]
+pub fn with_group_tracker<R>(body: impl FnOnce(&mut GroupTracker) -> R) -> R {
+    body(&mut GROUP_TRACKER.lock().unwrap())
+}
+
+/// Registers a callback invoked for every change of the shared [`GroupTracker`].
+///
+/// Returns a [`Revertible`] to remove the callback.
+pub fn on_group_change(
+    callback: impl Fn(&GroupChange) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register((), callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(account_name: &str, subgroup: u32) -> GroupMemberOwned {
+        GroupMemberOwned {
+            account_name: account_name.into(),
+            character_name: "Character".into(),
+            subgroup,
+            profession: 1,
+            elite_specialization: 27,
+            is_self: account_name == "Self.1234",
+            is_in_instance: true,
+            is_commander: false,
+            is_lieutenant: false,
+        }
+    }
+
+    #[test]
+    fn diffs_and_count() {
+        let mut tracker = GroupTracker::new();
+        tracker.join(member("Self.1234", 1));
+        tracker.join(member("Other.1234", 1));
+
+        let mut moved = member("Other.1234", 2);
+        moved.is_commander = true;
+        assert_eq!(
+            tracker.update(moved),
+            [
+                GroupChange::SubgroupChanged {
+                    account_name: "Other.1234".into(),
+                    from: 1,
+                    to: 2
+                },
+                GroupChange::CommanderChanged {
+                    account_name: "Other.1234".into(),
+                    is_commander: true
+                }
+            ]
+        );
+        assert_eq!(tracker.commander().unwrap().account_name, "Other.1234");
+
+        let mut respecced = member("Self.1234", 1);
+        respecced.profession = 2;
+        respecced.elite_specialization = 18;
+        assert_eq!(
+            tracker.update(respecced),
+            [GroupChange::SpecializationChanged {
+                account_name: "Self.1234".into(),
+                profession: Ok(Profession::Warrior),
+                specialization: Ok(Specialization::Berserker),
+            }]
+        );
+
+        assert!(tracker.check_count(2).is_empty());
+        assert_eq!(
+            tracker.check_count(3),
+            [GroupChange::CountMismatch {
+                tracked: 2,
+                expected: 3
+            }]
+        );
+        assert_eq!(tracker.len(), 2);
+        assert!(tracker
+            .check_count(0)
+            .iter()
+            .all(|change| matches!(change, GroupChange::Left(_))));
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn count_drops_before_leave() {
+        let mut tracker = GroupTracker::new();
+        tracker.join(member("Self.1234", 1));
+        tracker.join(member("Other.1234", 1));
+        tracker.join(member("Leaving.1234", 2));
+
+        // member count drops before the leave event is delivered
+        assert_eq!(
+            tracker.check_count(2),
+            [GroupChange::CountMismatch {
+                tracked: 3,
+                expected: 2
+            }]
+        );
+        assert_eq!(tracker.len(), 3);
+
+        let changes = tracker.leave("Leaving.1234");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].account_name(), Some("Leaving.1234"));
+        assert!(tracker.check_count(2).is_empty());
+        assert!(tracker.get("Self.1234").is_some());
+        assert!(tracker.get("Other.1234").is_some());
+    }
+}
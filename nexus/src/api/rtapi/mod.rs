@@ -15,11 +15,13 @@ mod camera;
 pub mod data;
 pub mod event;
 mod game;
-mod group;
+pub mod group;
 mod player;
+mod snapshot;
+pub mod watcher;
 mod world;
 
-pub use self::{camera::*, game::*, group::*, player::*, world::*};
+pub use self::{camera::*, game::*, group::*, player::*, snapshot::*, world::*};
 
 use self::data::RealTimeData;
 use std::ptr::NonNull;
@@ -1,4 +1,8 @@
 use super::RealTimeData;
+use crate::{
+    game::{impl_game_ids, MountIndex},
+    vec3::Vec3,
+};
 use bitflags::bitflags;
 use std::ffi::CStr;
 
@@ -30,6 +34,11 @@ pub struct PlayerData {
     pub character_state: CharacterState,
 }
 
+impl_game_ids!(PlayerData {
+    profession,
+    elite_specialization
+});
+
 impl PlayerData {
     /// Reads player data from the given data pointer.
     ///
@@ -40,7 +49,7 @@ impl PlayerData {
             account_name: CStr::from_ptr((*data).account_name.as_ptr())
                 .to_string_lossy()
                 .into_owned(),
-            character_name: CStr::from_ptr((*data).account_name.as_ptr())
+            character_name: CStr::from_ptr((*data).character_name.as_ptr())
                 .to_string_lossy()
                 .into_owned(),
             character_position: (*data).character_position,
@@ -51,6 +60,24 @@ impl PlayerData {
             character_state: CharacterState::from_bits_retain((*data).character_state),
         }
     }
+
+    /// Returns the character position as [`Vec3`].
+    #[inline]
+    pub fn position(&self) -> Vec3 {
+        self.character_position.into()
+    }
+
+    /// Returns the character facing direction as [`Vec3`].
+    #[inline]
+    pub fn facing(&self) -> Vec3 {
+        self.character_facing.into()
+    }
+
+    /// Returns the [`MountIndex`] of the character.
+    #[inline]
+    pub fn mount(&self) -> Result<MountIndex, u32> {
+        self.mount_index.try_into()
+    }
 }
 
 bitflags! {
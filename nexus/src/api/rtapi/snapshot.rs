@@ -0,0 +1,152 @@
+use super::{CameraData, GameData, GroupData, PlayerData, RealTimeApi, RealTimeData, WorldData};
+use std::mem;
+
+/// Number of words in [`RealTimeData`].
+const WORDS: usize = mem::size_of::<RealTimeData>() / mem::size_of::<u32>();
+
+// raw copies rely on the data consisting of 4 byte fields without padding
+const _: () = assert!(mem::size_of::<RealTimeData>() % mem::size_of::<u32>() == 0);
+const _: () = assert!(mem::align_of::<RealTimeData>() <= mem::align_of::<u32>());
+
+/// Raw copy of [`RealTimeData`].
+type RawData = [u32; WORDS];
+
+/// Consistent copy of all RealTime API data.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RealTimeSnapshot {
+    /// Game data.
+    pub game: GameData,
+
+    /// World data.
+    pub world: WorldData,
+
+    /// Player data.
+    pub player: PlayerData,
+
+    /// Group data.
+    pub group: GroupData,
+
+    /// Camera data.
+    pub camera: CameraData,
+}
+
+impl RealTimeSnapshot {
+    /// Creates a snapshot from the given data.
+    #[inline]
+    pub fn from_data(data: &RealTimeData) -> Self {
+        let data: *const RealTimeData = data;
+        unsafe {
+            Self {
+                game: GameData::read(data),
+                world: WorldData::read(data),
+                player: PlayerData::read(data),
+                group: GroupData::read(data),
+                camera: CameraData::read(data),
+            }
+        }
+    }
+}
+
+impl RealTimeApi {
+    /// Maximum number of reads attempted by [`snapshot`](Self::snapshot).
+    pub const SNAPSHOT_ATTEMPTS: usize = 16;
+
+    /// Reads a consistent [`RealTimeSnapshot`].
+    ///
+    /// The data is read repeatedly until two consecutive reads are identical,
+    /// to avoid fields from different updates when the data is written concurrently.
+    /// Returns [`None`] if the data is not stable after [`SNAPSHOT_ATTEMPTS`](Self::SNAPSHOT_ATTEMPTS) reads,
+    /// the link is inactive or the game state is invalid.
+    pub fn snapshot(&self) -> Option<RealTimeSnapshot> {
+        self.read_stable().and_then(validate)
+    }
+
+    /// Reads the [`RealTimeData`] until two consecutive reads are identical.
+    #[inline]
+    pub fn read_stable(&self) -> Option<RealTimeData> {
+        read_stable_with(|| self.read_raw())
+    }
+
+    #[inline]
+    fn read_raw(&self) -> RawData {
+        unsafe { self.as_ptr().cast::<RawData>().read_volatile() }
+    }
+}
+
+/// Reads raw data with the given function until two consecutive reads are identical.
+fn read_stable_with(mut read: impl FnMut() -> RawData) -> Option<RealTimeData> {
+    let mut previous = read();
+    for _ in 1..RealTimeApi::SNAPSHOT_ATTEMPTS {
+        let current = read();
+        if current == previous {
+            // all bit patterns are valid for the data
+            return Some(unsafe { current.as_ptr().cast::<RealTimeData>().read_unaligned() });
+        }
+        previous = current;
+    }
+    None
+}
+
+/// Creates a snapshot if the link is active & the game state is valid.
+fn validate(data: RealTimeData) -> Option<RealTimeSnapshot> {
+    let valid = data.game_build != 0 && GameData::is_valid_state(data.game_state);
+    valid.then(|| RealTimeSnapshot::from_data(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rtapi::GameState;
+    use std::ptr::NonNull;
+
+    fn raw(game_build: u32, game_state: u32) -> RawData {
+        let mut raw = [0; WORDS];
+        raw[0] = game_build;
+        raw[1] = game_state;
+        raw
+    }
+
+    #[test]
+    fn stable_read() {
+        let buffer = raw(12345, GameState::Gameplay.into());
+        let api = RealTimeApi(NonNull::from(&buffer).cast());
+
+        let data = api.read_stable().expect("stable buffer not read");
+        assert_eq!(data.game_build, 12345);
+        let snapshot = api.snapshot().expect("valid buffer rejected");
+        assert_eq!(snapshot.game.game_state, Ok(GameState::Gameplay));
+    }
+
+    #[test]
+    fn unstable_read() {
+        let mut reads = 0;
+        let changing = read_stable_with(|| {
+            reads += 1;
+            raw(reads, 0)
+        });
+        assert!(changing.is_none());
+        assert_eq!(reads as usize, RealTimeApi::SNAPSHOT_ATTEMPTS);
+
+        // settles after a few concurrent writes
+        let mut reads = 0;
+        let settling = read_stable_with(|| {
+            reads += 1;
+            raw(reads.min(3), 0)
+        });
+        assert_eq!(settling.map(|data| data.game_build), Some(3));
+        assert_eq!(reads, 4);
+    }
+
+    #[test]
+    fn invalid_data() {
+        let inactive = raw(0, GameState::Gameplay.into());
+        let api = RealTimeApi(NonNull::from(&inactive).cast());
+        assert!(api.read_stable().is_some());
+        assert!(api.snapshot().is_none());
+
+        let invalid_state = raw(12345, 100);
+        let api = RealTimeApi(NonNull::from(&invalid_state).cast());
+        assert!(api.snapshot().is_none());
+    }
+}
@@ -0,0 +1,311 @@
+//! Change notifications for RealTime API data.
+//!
+//! The watcher is polled once per frame and compares the current data with the previous frame.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::rtapi::{
+//!     watcher::{on_realtime_change, subscribe_realtime_watcher, ChangeKind, RealTimeChange},
+//!     CharacterState,
+//! };
+//!
+//! subscribe_realtime_watcher().revert_on_unload();
+//!
+//! on_realtime_change(ChangeKind::CharacterState, |change| {
+//!     if change.gained(CharacterState::IsInCombat) {
+//!         // entered combat
+//!     }
+//! })
+//! .revert_on_unload();
+//! ```
+
+use super::{CharacterState, GameState, GroupType, RealTimeApi, RealTimeSnapshot};
+use crate::{
+    callbacks::Callbacks,
+    game::MountIndex,
+    gui::{register_render, RenderType},
+    revertible::Revertible,
+};
+use std::sync::Mutex;
+
+/// Shared watcher.
+static WATCHER: Mutex<RealTimeWatcher> = Mutex::new(RealTimeWatcher::new());
+
+/// Registered change callbacks.
+static CALLBACKS: Callbacks<RealTimeChange, Option<ChangeKind>> = Callbacks::new();
+
+/// Kind of a [`RealTimeChange`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum ChangeKind {
+    Map,
+    GameState,
+    Character,
+    Mount,
+    CharacterState,
+    GroupType,
+}
+
+/// Change of RealTime API data between frames.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RealTimeChange {
+    /// Map changed.
+    Map { from: u32, to: u32 },
+
+    /// Game state changed.
+    GameState {
+        from: Result<GameState, u32>,
+        to: Result<GameState, u32>,
+    },
+
+    /// Character switched.
+    Character { from: String, to: String },
+
+    /// Mount changed.
+    Mount {
+        from: Result<MountIndex, u32>,
+        to: Result<MountIndex, u32>,
+    },
+
+    /// Character state flags changed.
+    CharacterState {
+        from: CharacterState,
+        to: CharacterState,
+    },
+
+    /// Group type changed.
+    GroupType {
+        from: Result<GroupType, u32>,
+        to: Result<GroupType, u32>,
+    },
+}
+
+impl RealTimeChange {
+    /// Returns the [`ChangeKind`] of the change.
+    #[inline]
+    pub fn kind(&self) -> ChangeKind {
+        match self {
+            Self::Map { .. } => ChangeKind::Map,
+            Self::GameState { .. } => ChangeKind::GameState,
+            Self::Character { .. } => ChangeKind::Character,
+            Self::Mount { .. } => ChangeKind::Mount,
+            Self::CharacterState { .. } => ChangeKind::CharacterState,
+            Self::GroupType { .. } => ChangeKind::GroupType,
+        }
+    }
+
+    /// Checks whether the character state flags were set with this change.
+    ///
+    /// For example [`CharacterState::IsInCombat`] for entering combat.
+    #[inline]
+    pub fn gained(&self, flags: CharacterState) -> bool {
+        match self {
+            Self::CharacterState { from, to } => !from.contains(flags) && to.contains(flags),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the character state flags were cleared with this change.
+    ///
+    /// For example [`CharacterState::IsGliding`] for landing.
+    #[inline]
+    pub fn lost(&self, flags: CharacterState) -> bool {
+        match self {
+            Self::CharacterState { from, to } => from.contains(flags) && !to.contains(flags),
+            _ => false,
+        }
+    }
+}
+
+/// Watched state of a frame.
+#[derive(Debug, Clone, PartialEq)]
+struct WatchState {
+    map_id: u32,
+    game_state: Result<GameState, u32>,
+    character_name: String,
+    mount: Result<MountIndex, u32>,
+    character_state: CharacterState,
+    group_type: Result<GroupType, u32>,
+}
+
+impl WatchState {
+    fn read(rtapi: &RealTimeApi) -> Option<Self> {
+        let RealTimeSnapshot {
+            game,
+            world,
+            player,
+            group,
+            ..
+        } = rtapi.snapshot()?;
+        Some(Self {
+            map_id: world.map_id,
+            game_state: game.game_state,
+            mount: player.mount(),
+            character_name: player.character_name,
+            character_state: player.character_state,
+            group_type: group.group_type,
+        })
+    }
+
+    fn diff(&self, current: &Self) -> Vec<RealTimeChange> {
+        let mut changes = Vec::new();
+        if self.game_state != current.game_state {
+            changes.push(RealTimeChange::GameState {
+                from: self.game_state,
+                to: current.game_state,
+            });
+        }
+        if self.map_id != current.map_id {
+            changes.push(RealTimeChange::Map {
+                from: self.map_id,
+                to: current.map_id,
+            });
+        }
+        if self.character_name != current.character_name {
+            changes.push(RealTimeChange::Character {
+                from: self.character_name.clone(),
+                to: current.character_name.clone(),
+            });
+        }
+        if self.mount != current.mount {
+            changes.push(RealTimeChange::Mount {
+                from: self.mount,
+                to: current.mount,
+            });
+        }
+        if self.character_state != current.character_state {
+            changes.push(RealTimeChange::CharacterState {
+                from: self.character_state,
+                to: current.character_state,
+            });
+        }
+        if self.group_type != current.group_type {
+            changes.push(RealTimeChange::GroupType {
+                from: self.group_type,
+                to: current.group_type,
+            });
+        }
+        changes
+    }
+}
+
+/// Detects changes of RealTime API data between polls.
+#[derive(Debug, Clone, Default)]
+pub struct RealTimeWatcher {
+    previous: Option<WatchState>,
+}
+
+impl RealTimeWatcher {
+    /// Creates a new watcher without previous state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { previous: None }
+    }
+
+    /// Polls the RealTime API & returns the changes since the last poll.
+    ///
+    /// The first poll and polls while the RealTime API is inactive return no changes.
+    pub fn poll(&mut self, rtapi: &RealTimeApi) -> Vec<RealTimeChange> {
+        let Some(current) = WatchState::read(rtapi) else {
+            return Vec::new();
+        };
+        let changes = self
+            .previous
+            .as_ref()
+            .map(|previous| previous.diff(&current))
+            .unwrap_or_default();
+        self.previous = Some(current);
+        changes
+    }
+
+    /// Clears the previous state.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Registers the shared [`RealTimeWatcher`] to be polled every frame.
+///
+/// Returns a [`Revertible`] to revert the register.
+pub fn subscribe_realtime_watcher() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    extern "C-unwind" fn poll_realtime_watcher() {
+        let Some(rtapi) = RealTimeApi::get() else {
+            return;
+        };
+        let changes = WATCHER.lock().unwrap().poll(&rtapi);
+        for change in &changes {
+            CALLBACKS.invoke_for(change, change.kind());
+        }
+    }
+
+    let render = register_render(RenderType::PreRender, poll_realtime_watcher).into_inner();
+    let revert = move || {
+        render();
+        WATCHER.lock().unwrap().reset();
+    };
+    revert.into()
+}
+
+/// Registers a callback invoked for changes of the given [`ChangeKind`].
+///
+/// Returns a [`Revertible`] to remove the callback.
+#[inline]
+pub fn on_realtime_change(
+    kind: ChangeKind,
+    callback: impl Fn(&RealTimeChange) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register(Some(kind), callback)
+}
+
+/// Registers a callback invoked for all changes.
+///
+/// Returns a [`Revertible`] to remove the callback.
+#[inline]
+pub fn on_any_realtime_change(
+    callback: impl Fn(&RealTimeChange) + Send + Sync + 'static,
+) -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    CALLBACKS.register(None, callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        let previous = WatchState {
+            map_id: 15,
+            game_state: Ok(GameState::Gameplay),
+            character_name: "Character".into(),
+            mount: Ok(MountIndex::None),
+            character_state: CharacterState::IsAlive,
+            group_type: Ok(GroupType::None),
+        };
+        let current = WatchState {
+            mount: Ok(MountIndex::Raptor),
+            character_state: CharacterState::IsAlive | CharacterState::IsInCombat,
+            ..previous.clone()
+        };
+
+        let changes = previous.diff(&current);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind(), ChangeKind::Mount);
+        assert!(changes[1].gained(CharacterState::IsInCombat));
+        assert!(!changes[1].lost(CharacterState::IsAlive));
+        assert!(previous.diff(&previous).is_empty());
+    }
+}
@@ -1,7 +1,10 @@
 use super::RealTimeData;
+use crate::vec3::Vec3;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use std::net::Ipv4Addr;
 
+pub use crate::tyria_time::TimeOfDay;
+
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WorldData {
@@ -35,31 +38,12 @@ impl WorldData {
             cursor: (*data).cursor,
         }
     }
-}
 
-#[derive(
-    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
-)]
-#[num_enum(error_type(name = u32, constructor = From::from))]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[cfg_attr(
-    feature = "strum",
-    derive(
-        strum::AsRefStr,
-        strum::Display,
-        strum::EnumCount,
-        strum::EnumIter,
-        strum::IntoStaticStr,
-        strum::VariantArray,
-        strum::VariantNames
-    )
-)]
-#[repr(u32)]
-pub enum TimeOfDay {
-    Dawn,
-    Day,
-    Dusk,
-    Night,
+    /// Returns the cursor location as [`Vec3`].
+    #[inline]
+    pub fn cursor_position(&self) -> Vec3 {
+        self.cursor.into()
+    }
 }
 
 #[derive(
@@ -0,0 +1,253 @@
+//! Overlay drawing squad markers with distance labels.
+//!
+//! Marker positions are read from the RealTime API and projected to the screen using [`Projection`].
+//! Drawing happens on the imgui background draw list, below all addon windows.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::squad_markers::{set_squad_marker_config, show_squad_markers, SquadMarkerConfig};
+//!
+//! set_squad_marker_config(SquadMarkerConfig {
+//!     max_distance: Some(5000.0),
+//!     ..SquadMarkerConfig::new()
+//! });
+//! show_squad_markers().revert_on_unload();
+//! ```
+
+use crate::{
+    data_link::read_nexus_link,
+    gui::{register_render, RenderType},
+    imgui::Ui,
+    projection::{Projection, Units},
+    render,
+    revertible::Revertible,
+    rtapi::RealTimeApi,
+    vec3::Vec3,
+};
+use std::sync::Mutex;
+
+/// Shared overlay configuration.
+static CONFIG: Mutex<SquadMarkerConfig> = Mutex::new(SquadMarkerConfig::new());
+
+/// Squad marker.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum SquadMarker {
+    Arrow,
+    Circle,
+    Heart,
+    Square,
+    Star,
+    Swirl,
+    Triangle,
+    Cross,
+}
+
+impl SquadMarker {
+    /// All markers in RealTime API order.
+    pub const ALL: [Self; 8] = [
+        Self::Arrow,
+        Self::Circle,
+        Self::Heart,
+        Self::Square,
+        Self::Star,
+        Self::Swirl,
+        Self::Triangle,
+        Self::Cross,
+    ];
+
+    /// Returns the default color of the marker.
+    pub const fn color(&self) -> [f32; 4] {
+        match self {
+            Self::Arrow => [0.95, 0.78, 0.25, 1.0],
+            Self::Circle => [0.35, 0.85, 0.35, 1.0],
+            Self::Heart => [0.75, 0.4, 0.95, 1.0],
+            Self::Square => [0.3, 0.55, 1.0, 1.0],
+            Self::Star => [0.35, 0.9, 0.9, 1.0],
+            Self::Swirl => [1.0, 0.5, 0.75, 1.0],
+            Self::Triangle => [0.9, 0.3, 0.9, 1.0],
+            Self::Cross => [0.95, 0.25, 0.25, 1.0],
+        }
+    }
+
+    /// Returns the symbol drawn for the marker.
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Arrow => "A",
+            Self::Circle => "O",
+            Self::Heart => "H",
+            Self::Square => "S",
+            Self::Star => "*",
+            Self::Swirl => "@",
+            Self::Triangle => "T",
+            Self::Cross => "X",
+        }
+    }
+}
+
+/// Squad marker overlay configuration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SquadMarkerConfig {
+    /// Radius of the drawn markers in pixels, multiplied by the UI scaling.
+    pub radius: f32,
+
+    /// Colors of the markers in RealTime API order.
+    pub colors: [[f32; 4]; 8],
+
+    /// Whether to draw distance labels.
+    pub show_distance: bool,
+
+    /// Units of the displayed distance.
+    pub distance_units: Units,
+
+    /// Maximum distance to draw markers at, in [`distance_units`](Self::distance_units).
+    pub max_distance: Option<f32>,
+
+    /// Units of the marker & character positions.
+    pub position_units: Units,
+
+    /// Whether to hide the markers outside of gameplay.
+    pub gameplay_only: bool,
+}
+
+impl SquadMarkerConfig {
+    /// Creates the default configuration.
+    pub const fn new() -> Self {
+        let mut colors = [[0.0; 4]; 8];
+        let mut i = 0;
+        while i < colors.len() {
+            colors[i] = SquadMarker::ALL[i].color();
+            i += 1;
+        }
+        Self {
+            radius: 12.0,
+            colors,
+            show_distance: true,
+            distance_units: Units::Inches,
+            max_distance: None,
+            position_units: Units::Inches,
+            gameplay_only: true,
+        }
+    }
+}
+
+impl Default for SquadMarkerConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the current squad marker overlay configuration.
+#[inline]
+pub fn squad_marker_config() -> SquadMarkerConfig {
+    CONFIG.lock().unwrap().clone()
+}
+
+/// Sets the squad marker overlay configuration.
+#[inline]
+pub fn set_squad_marker_config(config: SquadMarkerConfig) {
+    *CONFIG.lock().unwrap() = config;
+}
+
+/// Shows the squad marker overlay.
+///
+/// Returns a [`Revertible`] to hide the overlay again.
+#[inline]
+pub fn show_squad_markers() -> Revertible<impl Fn() + Send + Sync + Clone + 'static> {
+    register_render(RenderType::Render, render!(render_squad_markers))
+}
+
+/// Checks whether a marker position is set.
+///
+/// Unset markers are located at the origin.
+#[inline]
+pub fn is_marker_set(position: impl Into<Vec3>) -> bool {
+    position.into() != Vec3::ZERO
+}
+
+/// Draws the squad markers with the given configuration.
+///
+/// Can be used directly from a custom render callback instead of [`show_squad_markers`].
+pub fn draw_squad_markers(ui: &Ui, config: &SquadMarkerConfig) {
+    let Some(rtapi) = RealTimeApi::get() else {
+        return;
+    };
+    let Some(snapshot) = rtapi.snapshot() else {
+        return;
+    };
+    let Some(link) = read_nexus_link() else {
+        return;
+    };
+    if config.gameplay_only && !link.is_gameplay {
+        return;
+    }
+
+    let projection = Projection::from_link(&snapshot.camera, &link);
+    let units = config.position_units;
+    let player = units.position_to_meters(snapshot.player.position());
+    let radius = config.radius * link.scaling;
+    let draw_list = ui.get_background_draw_list();
+
+    for ((marker, position), color) in SquadMarker::ALL
+        .iter()
+        .zip(snapshot.group.squad_marker_positions())
+        .zip(config.colors)
+    {
+        if !is_marker_set(position) {
+            continue;
+        }
+        let distance = config
+            .distance_units
+            .meters_to(player.distance(units.position_to_meters(position)));
+        if config.max_distance.is_some_and(|max| distance > max) {
+            continue;
+        }
+        let Some([x, y]) = projection.project(position, units) else {
+            continue;
+        };
+
+        draw_list
+            .add_circle([x, y], radius, color)
+            .filled(true)
+            .build();
+        draw_list
+            .add_circle([x, y], radius, [0.0, 0.0, 0.0, 0.8])
+            .thickness(2.0)
+            .build();
+
+        let symbol = marker.symbol();
+        let [width, height] = ui.calc_text_size(symbol);
+        draw_list.add_text(
+            [x - width * 0.5, y - height * 0.5],
+            [0.0, 0.0, 0.0, 1.0],
+            symbol,
+        );
+
+        if config.show_distance {
+            let label = format!("{distance:.0}");
+            let [width, _] = ui.calc_text_size(&label);
+            let pos = [x - width * 0.5, y + radius + 2.0];
+            draw_list.add_text([pos[0] + 1.0, pos[1] + 1.0], [0.0, 0.0, 0.0, 1.0], &label);
+            draw_list.add_text(pos, [1.0, 1.0, 1.0, 1.0], &label);
+        }
+    }
+}
+
+fn render_squad_markers(ui: &Ui) {
+    let config = squad_marker_config();
+    draw_squad_markers(ui, &config);
+}
@@ -0,0 +1,121 @@
+//! TacO `.trl` trail files.
+//!
+//! Trail files start with a version & map id, followed by the trail points as 3 floats each.
+//! All values are little endian.
+
+use crate::vec3::Vec3;
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+/// Trail file format version.
+pub const TRAIL_VERSION: u32 = 0;
+
+/// Size of the trail file header in bytes.
+const HEADER_SIZE: usize = 8;
+
+/// Size of a trail point in bytes.
+const POINT_SIZE: usize = 12;
+
+/// Trail points on a map.
+///
+/// Points are in meters.
+/// A point at the origin separates disconnected segments.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrailData {
+    /// Map id of the trail.
+    pub map_id: u32,
+
+    /// Trail points.
+    pub points: Vec<Vec3>,
+}
+
+impl TrailData {
+    /// Creates a new empty trail for the given map.
+    #[inline]
+    pub const fn new(map_id: u32) -> Self {
+        Self {
+            map_id,
+            points: Vec::new(),
+        }
+    }
+
+    /// Parses trail data from the `.trl` binary format.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        if bytes.len() < HEADER_SIZE || (bytes.len() - HEADER_SIZE) % POINT_SIZE != 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "invalid trail data size",
+            ));
+        }
+        let (header, points) = bytes.split_at(HEADER_SIZE);
+        let map_id = u32::from_le_bytes(header[4..8].try_into().unwrap());
+        let points = points
+            .chunks_exact(POINT_SIZE)
+            .map(|point| {
+                let [x, y, z] =
+                    [0, 4, 8].map(|i| f32::from_le_bytes(point[i..i + 4].try_into().unwrap()));
+                Vec3::new(x, y, z)
+            })
+            .collect();
+        Ok(Self { map_id, points })
+    }
+
+    /// Converts the trail data to the `.trl` binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + POINT_SIZE * self.points.len());
+        bytes.extend(TRAIL_VERSION.to_le_bytes());
+        bytes.extend(self.map_id.to_le_bytes());
+        for point in &self.points {
+            for value in point.to_array() {
+                bytes.extend(value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Loads trail data from a `.trl` file.
+    #[inline]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Saves the trail data to a `.trl` file.
+    #[inline]
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Returns an iterator over the connected segments of the trail.
+    #[inline]
+    pub fn segments(&self) -> impl Iterator<Item = &[Vec3]> {
+        self.points
+            .split(|point| *point == Vec3::ZERO)
+            .filter(|segment| !segment.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut data = TrailData::new(15);
+        data.points = vec![
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::ZERO,
+            Vec3::new(4.0, 5.0, 6.0),
+        ];
+        let bytes = data.to_bytes();
+        assert_eq!(bytes.len(), HEADER_SIZE + 3 * POINT_SIZE);
+        assert_eq!(&bytes[4..8], &15u32.to_le_bytes());
+        assert_eq!(TrailData::from_bytes(&bytes).unwrap(), data);
+        assert_eq!(data.segments().count(), 2);
+
+        assert!(TrailData::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! Tyrian time & day/night cycle.
+//!
+//! A Tyrian day lasts 2 real hours, starting at Tyrian midnight every even UTC hour.
+//! One real second equals 12 Tyrian seconds.
+//! Phases follow the cycle of Central Tyria:
+//!
+//! | Phase | Tyrian time | Real minutes into cycle |
+//! | --- | --- | --- |
+//! | Night | 21:00 - 05:00 | 105 - 25 |
+//! | Dawn | 05:00 - 06:00 | 25 - 30 |
+//! | Day | 06:00 - 20:00 | 30 - 100 |
+//! | Dusk | 20:00 - 21:00 | 100 - 105 |
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::tyria_time::{time_until, TimeOfDay, TyrianTime};
+//!
+//! let time = TyrianTime::now();
+//! println!("{time} ({})", time.time_of_day());
+//!
+//! let countdown = time_until(TimeOfDay::Night);
+//! ```
+
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Duration of a day/night cycle in real seconds.
+pub const CYCLE_SECONDS: u64 = 2 * 60 * 60;
+
+/// Duration of a day/night cycle.
+pub const CYCLE: Duration = Duration::from_secs(CYCLE_SECONDS);
+
+/// Tyrian seconds per real second.
+pub const TIME_SCALE: u64 = 12;
+
+/// Tyrian seconds per Tyrian day.
+pub const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// Phase of the Tyrian day.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[num_enum(error_type(name = u32, constructor = From::from))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+#[repr(u32)]
+pub enum TimeOfDay {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+impl TimeOfDay {
+    /// Returns the start of the phase in real seconds since the cycle start.
+    #[inline]
+    pub const fn cycle_start(&self) -> u64 {
+        match self {
+            Self::Dawn => 25 * 60,
+            Self::Day => 30 * 60,
+            Self::Dusk => 100 * 60,
+            Self::Night => 105 * 60,
+        }
+    }
+
+    /// Returns the Tyrian hour the phase starts at.
+    #[inline]
+    pub const fn start_hour(&self) -> u32 {
+        match self {
+            Self::Dawn => 5,
+            Self::Day => 6,
+            Self::Dusk => 20,
+            Self::Night => 21,
+        }
+    }
+
+    /// Returns the duration of the phase.
+    #[inline]
+    pub const fn duration(&self) -> Duration {
+        let end = match self {
+            Self::Night => Self::Dawn.cycle_start() + CYCLE_SECONDS,
+            _ => self.next().cycle_start(),
+        };
+        Duration::from_secs(end - self.cycle_start())
+    }
+
+    /// Returns the following phase.
+    #[inline]
+    pub const fn next(&self) -> Self {
+        match self {
+            Self::Dawn => Self::Day,
+            Self::Day => Self::Dusk,
+            Self::Dusk => Self::Night,
+            Self::Night => Self::Dawn,
+        }
+    }
+
+    /// Returns the phase at the given real seconds since the cycle start.
+    #[inline]
+    pub const fn from_cycle_seconds(seconds: u64) -> Self {
+        let seconds = seconds % CYCLE_SECONDS;
+        if seconds < Self::Dawn.cycle_start() {
+            Self::Night
+        } else if seconds < Self::Day.cycle_start() {
+            Self::Dawn
+        } else if seconds < Self::Dusk.cycle_start() {
+            Self::Day
+        } else if seconds < Self::Night.cycle_start() {
+            Self::Dusk
+        } else {
+            Self::Night
+        }
+    }
+
+    /// Returns the phase at the given time.
+    #[inline]
+    pub fn at(time: SystemTime) -> Self {
+        Self::from_cycle_seconds(cycle_offset(time).as_secs())
+    }
+
+    /// Returns the current phase.
+    #[inline]
+    pub fn now() -> Self {
+        Self::at(SystemTime::now())
+    }
+}
+
+/// Tyrian time of day.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TyrianTime {
+    /// Tyrian seconds since midnight.
+    pub seconds: u32,
+}
+
+impl TyrianTime {
+    /// Creates a Tyrian time from Tyrian seconds since midnight.
+    #[inline]
+    pub const fn from_seconds(seconds: u32) -> Self {
+        Self {
+            seconds: seconds % SECONDS_PER_DAY,
+        }
+    }
+
+    /// Creates a Tyrian time from hours & minutes.
+    #[inline]
+    pub const fn from_hm(hour: u32, minute: u32) -> Self {
+        Self::from_seconds(hour * 3600 + minute * 60)
+    }
+
+    /// Returns the Tyrian time at the given time.
+    #[inline]
+    pub fn at(time: SystemTime) -> Self {
+        let offset = cycle_offset(time);
+        Self::from_seconds((offset.as_secs() * TIME_SCALE) as u32)
+    }
+
+    /// Returns the current Tyrian time.
+    #[inline]
+    pub fn now() -> Self {
+        Self::at(SystemTime::now())
+    }
+
+    /// Returns the hour.
+    #[inline]
+    pub const fn hour(&self) -> u32 {
+        self.seconds / 3600
+    }
+
+    /// Returns the minute.
+    #[inline]
+    pub const fn minute(&self) -> u32 {
+        self.seconds / 60 % 60
+    }
+
+    /// Returns the second.
+    #[inline]
+    pub const fn second(&self) -> u32 {
+        self.seconds % 60
+    }
+
+    /// Returns the phase of the day.
+    #[inline]
+    pub const fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay::from_cycle_seconds((self.seconds / TIME_SCALE as u32) as u64)
+    }
+}
+
+impl fmt::Display for TyrianTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
+
+/// Returns the real time passed since the start of the current cycle at the given time.
+#[inline]
+pub fn cycle_offset(time: SystemTime) -> Duration {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Duration::new(
+        since_epoch.as_secs() % CYCLE_SECONDS,
+        since_epoch.subsec_nanos(),
+    )
+}
+
+/// Returns the next start of the phase after the given time.
+///
+/// If the phase starts exactly at the given time, the start in the next cycle is returned.
+pub fn next_transition(time: SystemTime, phase: TimeOfDay) -> SystemTime {
+    time + time_until_at(time, phase)
+}
+
+/// Returns the next phase change after the given time.
+#[inline]
+pub fn next_phase_change(time: SystemTime) -> (TimeOfDay, SystemTime) {
+    let next = TimeOfDay::at(time).next();
+    (next, next_transition(time, next))
+}
+
+/// Returns the duration from the given time until the next start of the phase.
+pub fn time_until_at(time: SystemTime, phase: TimeOfDay) -> Duration {
+    let offset = cycle_offset(time);
+    let start = Duration::from_secs(phase.cycle_start());
+    if start > offset {
+        start - offset
+    } else {
+        CYCLE + start - offset
+    }
+}
+
+/// Returns the countdown until the next start of the phase.
+#[inline]
+pub fn time_until(phase: TimeOfDay) -> Duration {
+    time_until_at(SystemTime::now(), phase)
+}
+
+/// Returns the countdown until the next phase change.
+#[inline]
+pub fn time_until_next_phase() -> (TimeOfDay, Duration) {
+    let now = SystemTime::now();
+    let next = TimeOfDay::at(now).next();
+    (next, time_until_at(now, next))
+}
+
+/// Returns the current phase from the RealTime API, falling back to the system time.
+#[cfg(feature = "rtapi")]
+pub fn current_time_of_day() -> TimeOfDay {
+    crate::rtapi::RealTimeApi::get()
+        .and_then(|rtapi| rtapi.read_world())
+        .and_then(|world| world.time_of_day.ok())
+        .unwrap_or_else(TimeOfDay::now)
+}
+
+/// Compares the computed phase with the RealTime API.
+///
+/// Returns [`None`] if the RealTime API is unavailable.
+#[cfg(feature = "rtapi")]
+pub fn matches_rtapi() -> Option<bool> {
+    let world = crate::rtapi::RealTimeApi::get()?.read_world()?;
+    Some(world.time_of_day == Ok(TimeOfDay::now()))
+}
+
+/// Returns the current phase from the system time.
+#[cfg(not(feature = "rtapi"))]
+#[inline]
+pub fn current_time_of_day() -> TimeOfDay {
+    TimeOfDay::now()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(minutes: u64) -> SystemTime {
+        // arbitrary even UTC hour
+        UNIX_EPOCH
+            + Duration::from_secs(1_700_000_000 / CYCLE_SECONDS * CYCLE_SECONDS + minutes * 60)
+    }
+
+    #[test]
+    fn cycle() {
+        let expected = [
+            (0, "00:00", TimeOfDay::Night),
+            (25, "05:00", TimeOfDay::Dawn),
+            (30, "06:00", TimeOfDay::Day),
+            (60, "12:00", TimeOfDay::Day),
+            (100, "20:00", TimeOfDay::Dusk),
+            (105, "21:00", TimeOfDay::Night),
+            (120, "00:00", TimeOfDay::Night),
+        ];
+        for (minutes, time, phase) in expected {
+            let tyrian = TyrianTime::at(utc(minutes));
+            assert_eq!(tyrian.to_string(), time);
+            assert_eq!(tyrian.time_of_day(), phase);
+            assert_eq!(TimeOfDay::at(utc(minutes)), phase);
+        }
+
+        assert_eq!(next_phase_change(utc(10)), (TimeOfDay::Dawn, utc(25)));
+        assert_eq!(time_until_at(utc(30), TimeOfDay::Day), CYCLE);
+        assert_eq!(next_transition(utc(110), TimeOfDay::Day), utc(150));
+        assert_eq!(
+            [
+                TimeOfDay::Dawn,
+                TimeOfDay::Day,
+                TimeOfDay::Dusk,
+                TimeOfDay::Night
+            ]
+            .map(|phase| phase.duration()),
+            [5, 70, 5, 40].map(|minutes| Duration::from_secs(minutes * 60))
+        );
+    }
+
+    #[test]
+    fn rtapi_values() {
+        // raw RealTime API values
+        assert_eq!(TimeOfDay::try_from(0), Ok(TimeOfDay::Dawn));
+        assert_eq!(TimeOfDay::try_from(1), Ok(TimeOfDay::Day));
+        assert_eq!(TimeOfDay::try_from(2), Ok(TimeOfDay::Dusk));
+        assert_eq!(TimeOfDay::try_from(3), Ok(TimeOfDay::Night));
+    }
+}
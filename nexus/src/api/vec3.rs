@@ -0,0 +1,290 @@
+//! 3D vector for game positions & directions.
+//!
+//! The game uses a left-handed coordinate system with the Y axis pointing up.
+//! Map coordinates are in inches, MumbleLink coordinates are in meters.
+//!
+//! # Usage
+//! ```no_run
+//! use nexus::vec3::Vec3;
+//!
+//! let player = Vec3::new(10.0, 0.0, 20.0);
+//! let target = Vec3::from([13.0, 5.0, 24.0]);
+//!
+//! let distance = player.distance(target);
+//! let horizontal = player.horizontal_distance(target);
+//! let heading = (target - player).heading();
+//! ```
+
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Inches per meter.
+pub const INCHES_PER_METER: f32 = 39.3701;
+
+/// Units of game coordinates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(
+        strum::AsRefStr,
+        strum::Display,
+        strum::EnumCount,
+        strum::EnumIter,
+        strum::IntoStaticStr,
+        strum::VariantArray,
+        strum::VariantNames
+    )
+)]
+pub enum Units {
+    /// Meters, as used by MumbleLink & camera positions.
+    Meters,
+
+    /// Inches, as used by map data & game coordinates.
+    Inches,
+}
+
+impl Units {
+    /// Converts a value in these units to meters.
+    #[inline]
+    pub fn to_meters(self, value: f32) -> f32 {
+        match self {
+            Self::Meters => value,
+            Self::Inches => value / INCHES_PER_METER,
+        }
+    }
+
+    /// Converts a value in meters to these units.
+    #[inline]
+    pub fn meters_to(self, value: f32) -> f32 {
+        match self {
+            Self::Meters => value,
+            Self::Inches => value * INCHES_PER_METER,
+        }
+    }
+
+    /// Converts a position in these units to meters.
+    #[inline]
+    pub fn position_to_meters(self, position: impl Into<Vec3>) -> Vec3 {
+        let position = position.into();
+        match self {
+            Self::Meters => position,
+            Self::Inches => position.to_meters(),
+        }
+    }
+}
+
+/// 3D vector.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    /// Zero vector.
+    pub const ZERO: Self = Self::new(0.0, 0.0, 0.0);
+
+    /// Up direction.
+    pub const UP: Self = Self::new(0.0, 1.0, 0.0);
+
+    /// Creates a new vector.
+    #[inline]
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Converts the vector to an array.
+    #[inline]
+    pub const fn to_array(self) -> [f32; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Returns the dot product.
+    #[inline]
+    pub fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the cross product.
+    #[inline]
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Returns the length.
+    #[inline]
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the length ignoring height.
+    #[inline]
+    pub fn horizontal_length(self) -> f32 {
+        self.x.hypot(self.z)
+    }
+
+    /// Returns the vector scaled to length 1.
+    ///
+    /// The zero vector is returned unchanged.
+    #[inline]
+    pub fn normalize(self) -> Self {
+        let length = self.length();
+        if length > 0.0 {
+            self / length
+        } else {
+            self
+        }
+    }
+
+    /// Returns the distance to another point.
+    #[inline]
+    pub fn distance(self, other: Self) -> f32 {
+        (other - self).length()
+    }
+
+    /// Returns the distance to another point ignoring height.
+    #[inline]
+    pub fn horizontal_distance(self, other: Self) -> f32 {
+        (other - self).horizontal_length()
+    }
+
+    /// Returns the compass heading of the direction in radians.
+    ///
+    /// 0 points along the Z axis (north), increasing clockwise towards the X axis (east).
+    /// The result is in the range `0..TAU`.
+    #[inline]
+    pub fn heading(self) -> f32 {
+        self.x.atan2(self.z).rem_euclid(std::f32::consts::TAU)
+    }
+
+    /// Returns the compass heading of the direction in degrees.
+    #[inline]
+    pub fn heading_degrees(self) -> f32 {
+        self.heading().to_degrees()
+    }
+
+    /// Returns the angle between two directions in radians.
+    #[inline]
+    pub fn angle(self, other: Self) -> f32 {
+        let lengths = self.length() * other.length();
+        if lengths > 0.0 {
+            (self.dot(other) / lengths).clamp(-1.0, 1.0).acos()
+        } else {
+            0.0
+        }
+    }
+
+    /// Converts the vector from inches to meters.
+    #[inline]
+    pub fn to_meters(self) -> Self {
+        self / INCHES_PER_METER
+    }
+
+    /// Converts the vector from meters to inches.
+    #[inline]
+    pub fn to_inches(self) -> Self {
+        self * INCHES_PER_METER
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    #[inline]
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    #[inline]
+    fn from(vec: Vec3) -> Self {
+        vec.to_array()
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl SubAssign for Vec3 {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<f32> for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, factor: f32) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl Div<f32> for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, divisor: f32) -> Self {
+        Self::new(self.x / divisor, self.y / divisor, self.z / divisor)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn distance_and_heading() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(4.0, 6.0, 3.0);
+        assert_eq!(a.distance(b), 5.0);
+        assert_eq!(a.horizontal_distance(b), 3.0);
+
+        assert_eq!(Vec3::new(0.0, 0.0, 1.0).heading(), 0.0);
+        assert_eq!(Vec3::new(1.0, 0.0, 0.0).heading(), FRAC_PI_2);
+        assert_eq!(Vec3::new(0.0, 0.0, -1.0).heading(), PI);
+        assert!((Vec3::new(-1.0, 0.0, 0.0).heading_degrees() - 270.0).abs() < 0.001);
+
+        let inches = Vec3::new(INCHES_PER_METER, 0.0, 0.0);
+        assert_eq!(inches.to_meters(), Vec3::new(1.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::revertible::Revertible;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// Shared callback.
+pub type Callback<E> = Arc<dyn Fn(&E) + Send + Sync>;
+
+/// Registry of callbacks with an optional filter key.
+///
+/// Callbacks are invoked from a snapshot without holding the lock.
+/// This allows callbacks to register or remove callbacks, including themselves.
+pub struct Callbacks<E: ?Sized, K = ()> {
+    next_id: AtomicUsize,
+    entries: Mutex<Vec<(usize, K, Callback<E>)>>,
+}
+
+impl<E: ?Sized, K> Callbacks<E, K> {
+    /// Creates a new empty registry.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            next_id: AtomicUsize::new(0),
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Adds a callback with the given key.
+    ///
+    /// Returns the id of the callback.
+    pub fn add(&self, key: K, callback: impl Fn(&E) + Send + Sync + 'static) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.entries
+            .lock()
+            .unwrap()
+            .push((id, key, Arc::new(callback)));
+        id
+    }
+
+    /// Removes the callback with the given id.
+    #[inline]
+    pub fn remove(&self, id: usize) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|(callback_id, _, _)| *callback_id != id)
+    }
+
+    /// Adds a callback with the given key.
+    ///
+    /// Returns a [`Revertible`] to remove the callback.
+    #[inline]
+    pub fn register(
+        &'static self,
+        key: K,
+        callback: impl Fn(&E) + Send + Sync + 'static,
+    ) -> Revertible<impl Fn() + Send + Sync + Clone + 'static>
+    where
+        K: Send + 'static,
+        E: 'static,
+    {
+        let id = self.add(key, callback);
+        let revert = move || self.remove(id);
+        revert.into()
+    }
+
+    /// Returns the callbacks whose key matches the filter.
+    #[inline]
+    pub fn snapshot(&self, filter: impl Fn(&K) -> bool) -> Vec<Callback<E>> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, key, _)| filter(key))
+            .map(|(_, _, callback)| callback.clone())
+            .collect()
+    }
+
+    /// Invokes the callbacks whose key matches the filter.
+    #[inline]
+    pub fn invoke_filtered(&self, event: &E, filter: impl Fn(&K) -> bool) {
+        for callback in self.snapshot(filter) {
+            callback(event);
+        }
+    }
+
+    /// Invokes all callbacks.
+    #[inline]
+    pub fn invoke(&self, event: &E) {
+        self.invoke_filtered(event, |_| true)
+    }
+}
+
+impl<E: ?Sized, K> Default for Callbacks<E, K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: ?Sized, K: PartialEq> Callbacks<E, Option<K>> {
+    /// Invokes the callbacks registered for the given key or for all keys.
+    #[inline]
+    pub fn invoke_for(&self, event: &E, key: K) {
+        self.invoke_filtered(event, |filter| {
+            filter.as_ref().map_or(true, |filter| *filter == key)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn reentrant() {
+        static CALLBACKS: Callbacks<u32> = Callbacks::new();
+        static REVERT: Mutex<Option<Box<dyn FnOnce() + Send>>> = Mutex::new(None);
+        static CALLED: AtomicBool = AtomicBool::new(false);
+
+        let revert = CALLBACKS
+            .register((), |value| {
+                // register another callback & remove self from within a callback
+                CALLBACKS.add((), |_| CALLED.store(true, Ordering::Relaxed));
+                if let Some(revert) = REVERT.lock().unwrap().take() {
+                    revert();
+                }
+                assert_eq!(*value, 1);
+            })
+            .into_inner();
+        *REVERT.lock().unwrap() = Some(Box::new(revert));
+
+        CALLBACKS.invoke(&1);
+        assert_eq!(CALLBACKS.snapshot(|_| true).len(), 1);
+        assert!(!CALLED.load(Ordering::Relaxed));
+
+        CALLBACKS.invoke(&2);
+        assert!(CALLED.load(Ordering::Relaxed));
+    }
+}
@@ -49,6 +49,14 @@ pub unsafe fn init(
         .expect("imgui context initialized multiple times");
 }
 
+/// Initializes only the [`AddonApi`] global, without ImGui, logger or panic hook.
+///
+/// Returns `false` if the [`AddonApi`] was already initialized.
+#[cfg(feature = "json")]
+pub(crate) fn init_addon_api(api: &'static AddonApi) -> bool {
+    ADDON_API.set(api).is_ok()
+}
+
 /// Actions to be performed on addon unload.
 static UNLOAD_ACTIONS: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
 
@@ -24,6 +24,7 @@
 
 pub mod addon;
 mod api;
+mod callbacks;
 mod globals;
 mod revertible;
 mod util;
@@ -1,15 +1,15 @@
 use nexus::{
-    event::{arc::ACCOUNT_NAME, event_subscribe},
-    event_consume,
+    account::{on_account_name_change, subscribe_account_name},
+    event::event_subscribe,
     gui::{register_render, render, RenderType},
-    imgui::{sys::cty::c_char, Window},
+    imgui::Window,
     keybind::{keybind_handler, register_keybind_with_string},
     paths::get_addon_dir,
     quick_access::{add_quick_access, add_quick_access_context_menu},
     texture::{load_texture_from_file, texture_receive, Texture},
     AddonFlags, UpdateProvider,
 };
-use std::{cell::Cell, ffi::CStr};
+use std::cell::Cell;
 
 nexus::export! {
     name: "Example Addon",
@@ -85,14 +85,8 @@ fn load() {
     unsafe { event_subscribe!("MY_EVENT" => i32, |data| log::info!("Received event {data:?}")) }
         .revert_on_unload();
 
-    ACCOUNT_NAME
-        .subscribe(event_consume!(<c_char> |name| {
-            if let Some(name) = name {
-                let name = unsafe {CStr::from_ptr(name as *const c_char)};
-                log::info!("Received account name: {name:?}");
-            }
-        }))
-        .revert_on_unload();
+    on_account_name_change(|name| log::info!("Received account name: {name}")).revert_on_unload();
+    subscribe_account_name().revert_on_unload();
 }
 
 fn unload() {